@@ -0,0 +1,204 @@
+//! Bitboard backend for fast move generation.
+//!
+//! Square (`row`, `col`) maps to bit `row * 8 + col`. Legal moves and flips
+//! are computed for all eight directions at once with Kogge-Stone fills.
+//! Only the standard 8x8 board fits in a bitboard.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::board::{Board, BOARD_SIZE};
+use crate::cell::Cell;
+
+// Every square except the a- and h-files, used to stop fills wrapping
+// around the board edge on horizontal and diagonal shifts.
+const INNER_COLS: u64 = 0x7e7e_7e7e_7e7e_7e7e;
+
+// Shift amounts and propagator masks for each direction. Positive shifts
+// move towards higher bits (east/south), negative ones towards lower bits.
+const SHIFTS: [(i32, u64); 8] = [
+    (1, INNER_COLS),  // east
+    (-1, INNER_COLS), // west
+    (8, !0),          // south
+    (-8, !0),         // north
+    (9, INNER_COLS),  // south-east
+    (7, INNER_COLS),  // south-west
+    (-7, INNER_COLS), // north-east
+    (-9, INNER_COLS), // north-west
+];
+
+#[inline]
+fn shift(bits: u64, amount: i32) -> u64 {
+    if amount > 0 {
+        bits << amount
+    } else {
+        bits >> -amount
+    }
+}
+
+// Occluded fill: extend `gen` along `amount` through the squares in `pro`.
+#[inline]
+fn fill(mut gen: u64, mut pro: u64, amount: i32) -> u64 {
+    gen |= pro & shift(gen, amount);
+    pro &= shift(pro, amount);
+    gen |= pro & shift(gen, 2 * amount);
+    pro &= shift(pro, 2 * amount);
+    gen |= pro & shift(gen, 4 * amount);
+    gen
+}
+
+/// Bit for the square at (`row`, `col`).
+#[inline]
+pub fn square_bit(row: usize, col: usize) -> u64 {
+    1u64 << (row * BOARD_SIZE + col)
+}
+
+/// A board stored as one bit mask per colour.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bitboard {
+    pub black: u64,
+    pub white: u64,
+}
+
+/// Error converting a board that is not 8x8 into a [`Bitboard`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct UnsupportedSize(pub usize);
+
+impl fmt::Display for UnsupportedSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a {0}x{0} board does not fit in a bitboard", self.0)
+    }
+}
+
+impl Error for UnsupportedSize {}
+
+impl TryFrom<&Board> for Bitboard {
+    type Error = UnsupportedSize;
+
+    fn try_from(board: &Board) -> Result<Self, Self::Error> {
+        if board.size() != BOARD_SIZE {
+            return Err(UnsupportedSize(board.size()));
+        }
+        let mut bitboard = Bitboard::default();
+        for row in 0..BOARD_SIZE {
+            for col in 0..BOARD_SIZE {
+                match board.get(row, col) {
+                    Cell::Black => bitboard.black |= square_bit(row, col),
+                    Cell::White => bitboard.white |= square_bit(row, col),
+                    Cell::Empty => {}
+                }
+            }
+        }
+        Ok(bitboard)
+    }
+}
+
+impl From<&Bitboard> for Board {
+    fn from(bitboard: &Bitboard) -> Self {
+        let mut board = Board::empty();
+        for row in 0..BOARD_SIZE {
+            for col in 0..BOARD_SIZE {
+                board.set(row, col, bitboard.get(row, col));
+            }
+        }
+        board
+    }
+}
+
+impl Bitboard {
+    /// Create a new bitboard with the four starting discs in the centre.
+    pub fn new() -> Self {
+        Bitboard::try_from(&Board::new()).expect("the standard board fits")
+    }
+
+    /// Get the contents of a square.
+    pub fn get(&self, row: usize, col: usize) -> Cell {
+        let bit = square_bit(row, col);
+        if self.black & bit != 0 {
+            Cell::Black
+        } else if self.white & bit != 0 {
+            Cell::White
+        } else {
+            Cell::Empty
+        }
+    }
+
+    /// Mask of the empty squares.
+    pub fn empty(&self) -> u64 {
+        !(self.black | self.white)
+    }
+
+    // Discs of `color` and of its opponent, in that order.
+    pub(crate) fn sides(&self, color: Cell) -> (u64, u64) {
+        match color {
+            Cell::Black => (self.black, self.white),
+            Cell::White => (self.white, self.black),
+            Cell::Empty => (0, 0),
+        }
+    }
+
+    /// Mask of every square where `color` has a legal move.
+    pub fn legal_moves(&self, color: Cell) -> u64 {
+        let (own, opp) = self.sides(color);
+        let mut moves = 0;
+        for &(amount, mask) in SHIFTS.iter() {
+            let captured = fill(own, opp & mask, amount) & !own;
+            moves |= shift(captured, amount);
+        }
+        moves & self.empty()
+    }
+
+    /// Mask of the discs flipped if `color` plays on the square `bit`.
+    ///
+    /// Returns 0 if the move captures nothing or the square is occupied.
+    pub fn flips(&self, bit: u64, color: Cell) -> u64 {
+        if bit & self.empty() == 0 {
+            return 0;
+        }
+        let (own, opp) = self.sides(color);
+        let mut flipped = 0;
+        for &(amount, mask) in SHIFTS.iter() {
+            let line = fill(bit, opp & mask, amount);
+            if shift(line, amount) & own != 0 {
+                flipped |= line & !bit;
+            }
+        }
+        flipped
+    }
+
+    /// Check if placing a disc of `color` at (`row`, `col`) is a legal move.
+    pub fn is_valid_move(&self, row: usize, col: usize, color: Cell) -> bool {
+        row < BOARD_SIZE && col < BOARD_SIZE && self.legal_moves(color) & square_bit(row, col) != 0
+    }
+
+    /// Place a disc of `color` at (`row`, `col`) and flip every captured disc.
+    ///
+    /// Returns the mask of flipped discs. Like [`Board::apply_move`], the
+    /// move is not validated.
+    pub fn apply_move(&mut self, row: usize, col: usize, color: Cell) -> u64 {
+        let bit = square_bit(row, col);
+        let flipped = self.flips(bit, color);
+        match color {
+            Cell::Black => {
+                self.black |= bit | flipped;
+                self.white &= !(bit | flipped);
+            }
+            Cell::White => {
+                self.white |= bit | flipped;
+                self.black &= !(bit | flipped);
+            }
+            Cell::Empty => {}
+        }
+        flipped
+    }
+
+    /// Check if `color` has at least one legal move.
+    pub fn has_valid_moves(&self, color: Cell) -> bool {
+        self.legal_moves(color) != 0
+    }
+
+    /// Count the black and white discs on the board, in that order.
+    pub fn count_pieces(&self) -> (usize, usize) {
+        (self.black.count_ones() as usize, self.white.count_ones() as usize)
+    }
+}
@@ -0,0 +1,296 @@
+use crate::bitboard::{square_bit, Bitboard};
+use crate::cell::Cell;
+use crate::zobrist::{self, square_key};
+
+/// Number of rows and columns on a standard board.
+pub const BOARD_SIZE: usize = 8;
+
+/// Smallest supported board size. Sizes must also be even.
+pub const MIN_SIZE: usize = 4;
+
+/// Largest supported board size.
+pub const MAX_SIZE: usize = 16;
+
+/// All eight directions a line of discs can run in, as (row, col) steps.
+const DIRECTIONS: [(isize, isize); 8] = [
+    (-1, -1), (-1, 0), (-1, 1),
+    (0, -1),           (0, 1),
+    (1, -1), (1, 0), (1, 1),
+];
+
+/// A move by the side to play: either a disc placed on a square or a pass.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Move {
+    Place { row: usize, col: usize },
+    Pass,
+}
+
+/// A legal placement together with the discs it would flip.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LegalMove {
+    pub row: usize,
+    pub col: usize,
+    /// Squares of the discs that change colour, as (row, col).
+    pub flips: Vec<(usize, usize)>,
+}
+
+impl LegalMove {
+    /// The placement as a [`Move`].
+    pub fn to_move(&self) -> Move {
+        Move::Place { row: self.row, col: self.col }
+    }
+}
+
+/// The game board: a square grid with an even side between [`MIN_SIZE`] and
+/// [`MAX_SIZE`], [`BOARD_SIZE`] unless chosen otherwise.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Board {
+    size: usize,
+    // Only the top-left `size` x `size` corner is used; the rest stays empty.
+    grid: [[Cell; MAX_SIZE]; MAX_SIZE],
+    // Zobrist key of the discs, kept up to date by every change to `grid`.
+    hash: u64,
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Board::new()
+    }
+}
+
+impl Board {
+    /// Create a new standard board with the four starting discs in the centre.
+    pub fn new() -> Self {
+        Board::with_size(BOARD_SIZE).expect("the standard size is supported")
+    }
+
+    /// Create a `size` x `size` board with the four starting discs in the
+    /// centre, or `None` if the size is not supported.
+    pub fn with_size(size: usize) -> Option<Self> {
+        let mut board = Board::empty_with_size(size)?;
+        let centre = size / 2;
+        board.set(centre - 1, centre - 1, Cell::White);
+        board.set(centre - 1, centre, Cell::Black);
+        board.set(centre, centre - 1, Cell::Black);
+        board.set(centre, centre, Cell::White);
+        Some(board)
+    }
+
+    /// Create a standard board with no discs on it.
+    pub fn empty() -> Self {
+        Board::empty_with_size(BOARD_SIZE).expect("the standard size is supported")
+    }
+
+    /// Create a `size` x `size` board with no discs on it, or `None` if the
+    /// size is not supported.
+    pub fn empty_with_size(size: usize) -> Option<Self> {
+        Board::is_valid_size(size).then_some(Board { size, grid: [[Cell::Empty; MAX_SIZE]; MAX_SIZE], hash: 0 })
+    }
+
+    /// Check if boards can be `size` squares wide: even, and between
+    /// [`MIN_SIZE`] and [`MAX_SIZE`].
+    pub fn is_valid_size(size: usize) -> bool {
+        (MIN_SIZE..=MAX_SIZE).contains(&size) && size.is_multiple_of(2)
+    }
+
+    /// Number of rows (and columns) on the board.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Get the contents of a square. Panics if the square is off the board.
+    pub fn get(&self, row: usize, col: usize) -> Cell {
+        assert!(row < self.size && col < self.size, "square ({}, {}) is off the board", row, col);
+        self.grid[row][col]
+    }
+
+    /// Put `cell` on a square without applying any rules. Panics if the
+    /// square is off the board.
+    pub fn set(&mut self, row: usize, col: usize, cell: Cell) {
+        assert!(row < self.size && col < self.size, "square ({}, {}) is off the board", row, col);
+        self.hash ^= square_key(row, col, self.grid[row][col]) ^ square_key(row, col, cell);
+        self.grid[row][col] = cell;
+    }
+
+    /// Zobrist key of the discs on the board, updated with every move.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Zobrist key of the board with `to_move` to play, for looking up
+    /// positions in a [`TranspositionTable`](crate::tt::TranspositionTable).
+    pub fn key(&self, to_move: Cell) -> u64 {
+        if to_move == Cell::White {
+            self.hash ^ zobrist::WHITE_TO_MOVE
+        } else {
+            self.hash
+        }
+    }
+
+    /// Print the current state of the board to stdout, with columns labelled
+    /// by letter and rows by number, as squares are named.
+    pub fn print(&self) {
+        print!("{}", self.grid_text());
+    }
+
+    /// The board as [`Board::print`] shows it, one line per row.
+    pub fn grid_text(&self) -> String {
+        let labels: String = (0..self.size).map(|i| (b'a' + i as u8) as char).collect();
+        let mut text = format!("   {}\n", labels);
+        for (i, row) in self.grid[..self.size].iter().enumerate() {
+            text.push_str(&format!("{:>2} ", i + 1));
+            text.extend(row[..self.size].iter().map(|cell| cell.to_char()));
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Check if placing a disc of `color` at (`row`, `col`) is a legal move.
+    pub fn is_valid_move(&self, row: usize, col: usize, color: Cell) -> bool {
+        // Check if the position is out of bounds or already occupied
+        if row >= self.size || col >= self.size || self.grid[row][col] != Cell::Empty {
+            return false;
+        }
+
+        // Iterate over each direction to see if it's a valid capturing move
+        for &(dr, dc) in DIRECTIONS.iter() {
+            let mut r = row as isize + dr;
+            let mut c = col as isize + dc;
+            let mut found_opposite = false;
+
+            while r >= 0 && r < self.size as isize && c >= 0 && c < self.size as isize {
+                match self.grid[r as usize][c as usize] {
+                    x if x == color.opposite() => found_opposite = true,
+                    x if x == color && found_opposite => return true, // Only valid if an opposite color is found first
+                    _ => break,
+                }
+                r += dr;
+                c += dc;
+            }
+        }
+        false
+    }
+
+    /// Place a disc of `color` at (`row`, `col`) and flip every captured disc.
+    ///
+    /// Returns the squares of the flipped discs so the move can be taken back
+    /// with [`Board::undo_move`]. The move is not validated; call
+    /// [`Board::is_valid_move`] first.
+    pub fn apply_move(&mut self, row: usize, col: usize, color: Cell) -> Vec<(usize, usize)> {
+        self.grid[row][col] = color;
+        self.hash ^= square_key(row, col, color);
+        let mut flipped = Vec::new();
+
+        for &(dr, dc) in DIRECTIONS.iter() {
+            let mut r = row as isize + dr;
+            let mut c = col as isize + dc;
+            let mut to_flip = Vec::new();
+
+            while r >= 0 && r < self.size as isize && c >= 0 && c < self.size as isize {
+                match self.grid[r as usize][c as usize] {
+                    x if x == color.opposite() => to_flip.push((r as usize, c as usize)),
+                    x if x == color => {
+                        for &(fr, fc) in to_flip.iter() {
+                            self.grid[fr][fc] = color; // Flip all in-between pieces to current color
+                            self.hash ^= square_key(fr, fc, color) ^ square_key(fr, fc, color.opposite());
+                        }
+                        flipped.extend(to_flip);
+                        break;
+                    }
+                    _ => break,
+                }
+                r += dr;
+                c += dc;
+            }
+        }
+        flipped
+    }
+
+    /// Take back a move made with [`Board::apply_move`]: empty (`row`, `col`)
+    /// and turn the `flipped` discs back over.
+    pub fn undo_move(&mut self, row: usize, col: usize, flipped: &[(usize, usize)]) {
+        self.hash ^= square_key(row, col, self.grid[row][col]);
+        self.grid[row][col] = Cell::Empty;
+        for &(r, c) in flipped.iter() {
+            let color = self.grid[r][c];
+            self.hash ^= square_key(r, c, color) ^ square_key(r, c, color.opposite());
+            self.grid[r][c] = color.opposite();
+        }
+    }
+
+    /// Check if `color` has at least one legal move.
+    pub fn has_valid_moves(&self, color: Cell) -> bool {
+        for row in 0..self.size {
+            for col in 0..self.size {
+                if self.is_valid_move(row, col, color) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Every legal move for `color` with the discs it flips, in row-major order.
+    pub fn legal_moves(&self, color: Cell) -> Vec<LegalMove> {
+        let Ok(bitboard) = Bitboard::try_from(self) else {
+            return self.scan_legal_moves(color);
+        };
+        squares(bitboard.legal_moves(color))
+            .into_iter()
+            .map(|(row, col)| LegalMove {
+                row,
+                col,
+                flips: squares(bitboard.flips(square_bit(row, col), color)),
+            })
+            .collect()
+    }
+
+    // Square-by-square version of `legal_moves` for boards too large for a bitboard.
+    fn scan_legal_moves(&self, color: Cell) -> Vec<LegalMove> {
+        let mut legal = Vec::new();
+        for row in 0..self.size {
+            for col in 0..self.size {
+                if self.is_valid_move(row, col, color) {
+                    let mut copy = *self;
+                    let mut flips = copy.apply_move(row, col, color);
+                    flips.sort_unstable();
+                    legal.push(LegalMove { row, col, flips });
+                }
+            }
+        }
+        legal
+    }
+
+    /// Count the black and white discs on the board, in that order.
+    pub fn count_pieces(&self) -> (usize, usize) {
+        let mut black_count = 0;
+        let mut white_count = 0;
+        for row in self.grid[..self.size].iter() {
+            for &cell in row[..self.size].iter() {
+                match cell {
+                    Cell::Black => black_count += 1,
+                    Cell::White => white_count += 1,
+                    _ => {}
+                }
+            }
+        }
+        (black_count, white_count)
+    }
+
+    /// Count the empty squares on the board.
+    pub fn count_empty(&self) -> usize {
+        let (black_count, white_count) = self.count_pieces();
+        self.size * self.size - black_count - white_count
+    }
+}
+
+// The (row, col) squares of every bit set in `mask`, in row-major order.
+fn squares(mut mask: u64) -> Vec<(usize, usize)> {
+    let mut squares = Vec::with_capacity(mask.count_ones() as usize);
+    while mask != 0 {
+        let square = mask.trailing_zeros() as usize;
+        squares.push((square / BOARD_SIZE, square % BOARD_SIZE));
+        mask &= mask - 1;
+    }
+    squares
+}
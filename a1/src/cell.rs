@@ -0,0 +1,27 @@
+/// The state of a single square on the board.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Cell {
+    Empty,
+    Black,
+    White,
+}
+
+impl Cell {
+    /// Get the opposite color. `Empty` maps to itself.
+    pub fn opposite(self) -> Cell {
+        match self {
+            Cell::Black => Cell::White,
+            Cell::White => Cell::Black,
+            Cell::Empty => Cell::Empty, // Default case; shouldn't be used for valid moves
+        }
+    }
+
+    /// Convert the cell to the character used when printing the board.
+    pub fn to_char(self) -> char {
+        match self {
+            Cell::Empty => '.',
+            Cell::Black => 'B',
+            Cell::White => 'W',
+        }
+    }
+}
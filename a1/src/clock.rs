@@ -0,0 +1,191 @@
+//! Chess clocks for timed games.
+//!
+//! Each side has main time and then either an increment added after every
+//! move (`5m+3`) or a number of byo-yomi periods (`5m/30x3`): once main time
+//! is used up, every move must be made within one period, and each period a
+//! move overruns is lost. A side that runs out of time loses.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// How much time each side gets.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TimeControl {
+    /// Main time, plus `increment` after every move.
+    Increment { main: Duration, increment: Duration },
+    /// Main time, then `periods` periods of `period` each.
+    ByoYomi { main: Duration, period: Duration, periods: u32 },
+}
+
+/// A time control that could not be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseTimeControlError(pub String);
+
+impl fmt::Display for ParseTimeControlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' is not MAIN[+INCREMENT] or MAIN/PERIODxCOUNT", self.0)
+    }
+}
+
+impl Error for ParseTimeControlError {}
+
+// Read a duration in seconds, or in minutes with an `m` suffix.
+fn parse_duration(text: &str) -> Option<Duration> {
+    let (number, scale) = match text.strip_suffix('m') {
+        Some(minutes) => (minutes, 60.0),
+        None => (text.strip_suffix('s').unwrap_or(text), 1.0),
+    };
+    let seconds: f64 = number.parse().ok()?;
+    // Too large for a `Duration` (or negative, or not a number) is an error
+    Duration::try_from_secs_f64(seconds * scale).ok()
+}
+
+impl FromStr for TimeControl {
+    type Err = ParseTimeControlError;
+
+    /// Read `MAIN[+INCREMENT]` or `MAIN/PERIODxCOUNT`, where each duration
+    /// is seconds or minutes with an `m` suffix, e.g. `5m+3` or `10m/30x3`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseTimeControlError(s.to_string());
+        let control = if let Some((main, byo_yomi)) = s.split_once('/') {
+            let (period, periods) = byo_yomi.split_once('x').ok_or_else(error)?;
+            TimeControl::ByoYomi {
+                main: parse_duration(main).ok_or_else(error)?,
+                period: parse_duration(period).filter(|period| !period.is_zero()).ok_or_else(error)?,
+                periods: periods.parse().ok().filter(|&periods| periods > 0).ok_or_else(error)?,
+            }
+        } else {
+            let (main, increment) = s.split_once('+').unwrap_or((s, "0"));
+            TimeControl::Increment {
+                main: parse_duration(main).ok_or_else(error)?,
+                increment: parse_duration(increment).ok_or_else(error)?,
+            }
+        };
+        Ok(control)
+    }
+}
+
+/// One side's clock.
+#[derive(Copy, Clone, Debug)]
+pub struct Clock {
+    control: TimeControl,
+    // Main time left, not counting the move in progress
+    remaining: Duration,
+    // Byo-yomi periods left
+    periods: u32,
+    // When the move in progress started, while the clock runs
+    started: Option<Instant>,
+    flagged: bool,
+}
+
+impl Clock {
+    /// A stopped clock with the full time of `control`.
+    pub fn new(control: TimeControl) -> Self {
+        let (remaining, periods) = match control {
+            TimeControl::Increment { main, .. } => (main, 0),
+            TimeControl::ByoYomi { main, periods, .. } => (main, periods),
+        };
+        Clock { control, remaining, periods, started: None, flagged: false }
+    }
+
+    /// Start timing a move. Does nothing if the clock is already running.
+    pub fn start(&mut self) {
+        if self.started.is_none() {
+            self.started = Some(Instant::now());
+        }
+    }
+
+    /// Stop timing a move and charge it to this side, adding any increment.
+    ///
+    /// Returns `false` if the move took longer than the time left.
+    pub fn stop(&mut self) -> bool {
+        if let Some(started) = self.started.take() {
+            self.record(started.elapsed());
+        }
+        !self.flagged
+    }
+
+    /// Charge a move that took `elapsed` to this side, as [`Clock::stop`]
+    /// does for a timed one, adding any increment.
+    ///
+    /// Returns `false` if the side has run out of time.
+    pub fn record(&mut self, elapsed: Duration) -> bool {
+        if self.flagged {
+            return false;
+        }
+        let (remaining, periods, flagged) = self.charge(elapsed);
+        self.remaining = remaining;
+        self.periods = periods;
+        self.flagged = flagged;
+        if let (TimeControl::Increment { increment, .. }, false) = (self.control, flagged) {
+            // Huge controls stop at the longest duration rather than overflow
+            self.remaining = self.remaining.saturating_add(increment);
+        }
+        !self.flagged
+    }
+
+    /// Check if this side has run out of time, counting the move in progress.
+    pub fn is_flagged(&self) -> bool {
+        self.flagged || self.started.is_some_and(|started| self.charge(started.elapsed()).2)
+    }
+
+    // Main time and periods left after a move that took `elapsed`, and
+    // whether it overran the clock.
+    fn charge(&self, elapsed: Duration) -> (Duration, u32, bool) {
+        if elapsed <= self.remaining {
+            return (self.remaining - elapsed, self.periods, false);
+        }
+        let overrun = elapsed - self.remaining;
+        match self.control {
+            TimeControl::Increment { .. } => (Duration::ZERO, 0, true),
+            TimeControl::ByoYomi { period, .. } => {
+                // A move within one period costs nothing; each period it runs past is lost
+                let lost = ((overrun.as_nanos() - 1) / period.as_nanos()) as u32;
+                match self.periods.checked_sub(lost) {
+                    Some(periods) if periods > 0 => (Duration::ZERO, periods, false),
+                    _ => (Duration::ZERO, 0, true),
+                }
+            }
+        }
+    }
+
+    /// How long to think about the next move with `empties` empty squares
+    /// on the board, spreading the time left over the moves still to come.
+    pub fn allot(&self, empties: usize) -> Duration {
+        // This side makes about half of the remaining moves
+        let moves_left = (empties as u32).div_ceil(2).max(1);
+        match self.control {
+            TimeControl::Increment { increment, .. } => {
+                // Keep half the clock in reserve so a slow move cannot lose on time
+                (self.remaining / moves_left).saturating_add(increment.saturating_mul(3) / 4).min(self.remaining / 2)
+            }
+            // A period is always left after main time, so most of one can be spent
+            TimeControl::ByoYomi { period, .. } => (self.remaining / moves_left).saturating_add(period.saturating_mul(4) / 5),
+        }
+    }
+}
+
+// Format a duration as `m:ss`, or `m:ss.s` under ten seconds.
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs_f64();
+    if seconds < 10.0 {
+        format!("0:{:04.1}", seconds)
+    } else {
+        let seconds = duration.as_secs();
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+impl fmt::Display for Clock {
+    /// The time left before the move in progress, such as `4:32`, with any
+    /// byo-yomi periods after it (`4:32+3x0:30`, then `3x0:30`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.control {
+            TimeControl::Increment { .. } => write!(f, "{}", format_duration(self.remaining)),
+            TimeControl::ByoYomi { period, .. } if self.remaining.is_zero() => write!(f, "{}x{}", self.periods, format_duration(period)),
+            TimeControl::ByoYomi { period, .. } => write!(f, "{}+{}x{}", format_duration(self.remaining), self.periods, format_duration(period)),
+        }
+    }
+}
@@ -0,0 +1,370 @@
+//! Exact endgame solver.
+//!
+//! Searches to the end of the game and returns the true
+//! final disc difference (or just win/loss/draw, which is faster). Moves are
+//! ordered fastest-first (fewest replies for the opponent) with a bonus for
+//! odd-parity regions, and subtrees are cut off when the opponent's stable
+//! discs already rule out beating alpha. Positions with many empties are
+//! cached in a [`TranspositionTable`]. Boards other than 8x8 fall back to
+//! a plain alpha-beta search.
+
+use std::sync::{Arc, OnceLock};
+
+use crate::bitboard::{square_bit, Bitboard};
+use crate::board::{Board, Move, BOARD_SIZE};
+use crate::cell::Cell;
+use crate::search::{disc_difference, Engine, SearchResult};
+use crate::tt::{Bound, TranspositionTable};
+use crate::zobrist::bitboard_key;
+
+/// Number of empty squares from which the computer player switches from the
+/// heuristic search to the exact solver.
+pub const SOLVE_EMPTIES: usize = 16;
+
+/// Check if the end of the game on `board` is close enough to solve exactly.
+///
+/// Boards larger than the standard one are too slow to solve.
+pub fn within_reach(board: &Board) -> bool {
+    board.count_empty() <= SOLVE_EMPTIES && board.size() <= BOARD_SIZE
+}
+
+// Below this many empties, ordering by mobility costs more than it saves.
+const FASTEST_FIRST_EMPTIES: u32 = 7;
+
+// From this many empties, positions are worth storing in the table.
+const TT_EMPTIES: u32 = 10;
+
+// Highest possible disc difference on a bitboard.
+const MAX_SCORE: i32 = (BOARD_SIZE * BOARD_SIZE) as i32;
+
+const QUADRANTS: [u64; 4] = [
+    0x0000_0000_0f0f_0f0f,
+    0x0000_0000_f0f0_f0f0,
+    0x0f0f_0f0f_0000_0000,
+    0xf0f0_f0f0_0000_0000,
+];
+
+const FILE_A: u64 = 0x0101_0101_0101_0101;
+const FILE_H: u64 = 0x8080_8080_8080_8080;
+const RANK_1: u64 = 0x0000_0000_0000_00ff;
+const RANK_8: u64 = 0xff00_0000_0000_0000;
+
+/// An exact solver for the end of the game.
+#[derive(Clone, Debug, Default)]
+pub struct Solver {
+    nodes: u64,
+    tt: Arc<TranspositionTable>,
+}
+
+impl Solver {
+    /// Create a solver.
+    pub fn new() -> Self {
+        Solver::default()
+    }
+
+    /// Find the move for `color` with the best final disc difference, and
+    /// that difference assuming perfect play by both sides.
+    pub fn solve_exact(&mut self, board: &Board, color: Cell) -> SearchResult {
+        let max_score = (board.size() * board.size()) as i32;
+        self.solve(board, color, -max_score, max_score)
+    }
+
+    /// Find a move for `color` that wins, or failing that draws.
+    ///
+    /// The score is 1 for a win, 0 for a draw and -1 for a loss.
+    pub fn solve_wld(&mut self, board: &Board, color: Cell) -> SearchResult {
+        let result = self.solve(board, color, -1, 1);
+        SearchResult { score: result.score.signum(), ..result }
+    }
+
+    /// The exact final disc difference after each legal move for `color`,
+    /// best first.
+    pub fn rank_moves(&mut self, board: &Board, color: Cell) -> Vec<SearchResult> {
+        let mut ranked: Vec<SearchResult> = board
+            .legal_moves(color)
+            .iter()
+            .map(|m| {
+                let mut next = *board;
+                next.apply_move(m.row, m.col, color);
+                let reply = self.solve_exact(&next, color.opposite());
+                SearchResult { best_move: m.to_move(), score: -reply.score, nodes: reply.nodes }
+            })
+            .collect();
+        ranked.sort_by_key(|result| -result.score);
+        ranked
+    }
+
+    fn solve(&mut self, board: &Board, color: Cell, alpha: i32, beta: i32) -> SearchResult {
+        self.nodes = 0;
+        self.tt.new_search();
+        let Ok(bitboard) = Bitboard::try_from(board) else {
+            return self.solve_board(board, color, alpha, beta);
+        };
+        let (own, opp) = bitboard.sides(color);
+        let moves = ordered_moves(own, opp);
+        if moves.is_empty() {
+            let score = -self.negamax(opp, own, -beta, -alpha, true);
+            return SearchResult { best_move: Move::Pass, score, nodes: self.nodes };
+        }
+
+        let mut best_move = Move::Pass;
+        let mut alpha = alpha;
+        let mut best_score = -MAX_SCORE - 1;
+        for (bit, flipped) in moves {
+            let (next_own, next_opp) = (own | bit | flipped, opp & !flipped);
+            let score = -self.negamax(next_opp, next_own, -beta, -alpha, false);
+            if score > best_score {
+                best_score = score;
+                let square = bit.trailing_zeros() as usize;
+                best_move = Move::Place { row: square / BOARD_SIZE, col: square % BOARD_SIZE };
+                if score >= beta {
+                    break;
+                }
+                alpha = alpha.max(score);
+            }
+        }
+        SearchResult { best_move, score: best_score, nodes: self.nodes }
+    }
+
+    // Plain alpha-beta over a `Board`, for sizes that do not fit a bitboard.
+    fn solve_board(&mut self, board: &Board, color: Cell, mut alpha: i32, beta: i32) -> SearchResult {
+        let mut board = *board;
+        let legal = board.legal_moves(color);
+        if legal.is_empty() {
+            let score = -self.negamax_board(&mut board, color.opposite(), -beta, -alpha, true);
+            return SearchResult { best_move: Move::Pass, score, nodes: self.nodes };
+        }
+        let mut best_move = Move::Pass;
+        let mut best_score = i32::MIN;
+        for m in legal {
+            let flipped = board.apply_move(m.row, m.col, color);
+            let score = -self.negamax_board(&mut board, color.opposite(), -beta, -alpha, false);
+            board.undo_move(m.row, m.col, &flipped);
+            if score > best_score {
+                best_score = score;
+                best_move = m.to_move();
+                if score >= beta {
+                    break;
+                }
+                alpha = alpha.max(score);
+            }
+        }
+        SearchResult { best_move, score: best_score, nodes: self.nodes }
+    }
+
+    fn negamax_board(&mut self, board: &mut Board, color: Cell, mut alpha: i32, beta: i32, passed: bool) -> i32 {
+        self.nodes += 1;
+        let legal = board.legal_moves(color);
+        if legal.is_empty() {
+            if passed {
+                let (black_count, white_count) = board.count_pieces();
+                let difference = black_count as i32 - white_count as i32;
+                return if color == Cell::Black { difference } else { -difference };
+            }
+            return -self.negamax_board(board, color.opposite(), -beta, -alpha, true);
+        }
+        let mut best = i32::MIN;
+        for m in legal {
+            let flipped = board.apply_move(m.row, m.col, color);
+            let score = -self.negamax_board(board, color.opposite(), -beta, -alpha, false);
+            board.undo_move(m.row, m.col, &flipped);
+            if score > best {
+                best = score;
+                if score >= beta {
+                    break;
+                }
+                alpha = alpha.max(score);
+            }
+        }
+        best
+    }
+
+    // Exact disc difference for the side owning `own`, within (alpha, beta).
+    fn negamax(&mut self, own: u64, opp: u64, mut alpha: i32, beta: i32, passed: bool) -> i32 {
+        self.nodes += 1;
+        let empties = !(own | opp);
+
+        // The opponent keeps its stable discs whatever happens.
+        if alpha >= 0 {
+            let upper = MAX_SCORE - 2 * stable_discs(opp, own).count_ones() as i32;
+            if upper <= alpha {
+                return upper;
+            }
+        }
+
+        if empties.count_ones() < FASTEST_FIRST_EMPTIES {
+            return self.negamax_shallow(own, opp, alpha, beta, passed);
+        }
+
+        // Keys are from the mover's side, so need no side to move.
+        let cached = empties.count_ones() >= TT_EMPTIES;
+        let key = if cached { bitboard_key(own, opp) } else { 0 };
+        let tt_move = match cached.then(|| self.tt.probe(key)).flatten() {
+            Some(entry) if entry.cuts_off(alpha, beta) => return entry.score,
+            Some(entry) => entry.best_move(),
+            None => None,
+        };
+
+        let mut moves = ordered_moves(own, opp);
+        if moves.is_empty() {
+            if passed {
+                return disc_difference(Bitboard { black: own, white: opp }.count_pieces(), Cell::Black);
+            }
+            return -self.negamax(opp, own, -beta, -alpha, true);
+        }
+        if let Some(Move::Place { row, col }) = tt_move {
+            if let Some(index) = moves.iter().position(|&(bit, _)| bit == square_bit(row, col)) {
+                moves[..=index].rotate_right(1);
+            }
+        }
+        let original_alpha = alpha;
+        let mut best = -MAX_SCORE - 1;
+        let mut best_bit = 0;
+        for (bit, flipped) in moves {
+            let score = -self.negamax(opp & !flipped, own | bit | flipped, -beta, -alpha, false);
+            if score > best {
+                best = score;
+                best_bit = bit;
+                if score >= beta {
+                    break;
+                }
+                alpha = alpha.max(score);
+            }
+        }
+        if cached {
+            // After failing low every move was just as bad, so none is best
+            let (bound, best_move) = if best >= beta {
+                (Bound::Lower, Some(best_bit))
+            } else if best > original_alpha {
+                (Bound::Exact, Some(best_bit))
+            } else {
+                (Bound::Upper, None)
+            };
+            let best_move = best_move.map(|bit| {
+                let square = bit.trailing_zeros() as usize;
+                Move::Place { row: square / BOARD_SIZE, col: square % BOARD_SIZE }
+            });
+            self.tt.store(key, empties.count_ones(), best, bound, best_move);
+        }
+        best
+    }
+
+    // Near the end, order by parity only: odd regions first, then the rest.
+    fn negamax_shallow(&mut self, own: u64, opp: u64, mut alpha: i32, beta: i32, passed: bool) -> i32 {
+        let empties = !(own | opp);
+        let moves = Bitboard { black: own, white: opp }.legal_moves(Cell::Black);
+        if moves == 0 {
+            if passed || empties == 0 {
+                return disc_difference(Bitboard { black: own, white: opp }.count_pieces(), Cell::Black);
+            }
+            return -self.negamax(opp, own, -beta, -alpha, true);
+        }
+        let odd = odd_regions(empties);
+        let mut best = -MAX_SCORE - 1;
+        for candidates in [moves & odd, moves & !odd] {
+            let mut candidates = candidates;
+            while candidates != 0 {
+                let bit = candidates & candidates.wrapping_neg();
+                candidates ^= bit;
+                let flipped = Bitboard { black: own, white: opp }.flips(bit, Cell::Black);
+                let score = -self.negamax(opp & !flipped, own | bit | flipped, -beta, -alpha, false);
+                if score > best {
+                    best = score;
+                    if score >= beta {
+                        return best;
+                    }
+                    alpha = alpha.max(score);
+                }
+            }
+        }
+        best
+    }
+}
+
+impl Engine for Solver {
+    fn choose_move(&mut self, board: &Board, color: Cell) -> Move {
+        self.solve_exact(board, color).best_move
+    }
+}
+
+// Union of the quadrants holding an odd number of empty squares.
+fn odd_regions(empties: u64) -> u64 {
+    QUADRANTS
+        .iter()
+        .filter(|&&quadrant| (empties & quadrant).count_ones() % 2 == 1)
+        .fold(0, |odd, &quadrant| odd | quadrant)
+}
+
+// Legal moves for `own` with their flips, fastest-first: moves leaving the
+// opponent fewest replies come first, and odd-parity regions break ties.
+fn ordered_moves(own: u64, opp: u64) -> Vec<(u64, u64)> {
+    let bitboard = Bitboard { black: own, white: opp };
+    let odd = odd_regions(bitboard.empty());
+    let mut moves = bitboard.legal_moves(Cell::Black);
+    let mut ordered = Vec::with_capacity(moves.count_ones() as usize);
+    while moves != 0 {
+        let bit = moves & moves.wrapping_neg();
+        moves ^= bit;
+        let flipped = bitboard.flips(bit, Cell::Black);
+        let after = Bitboard { black: opp & !flipped, white: own | bit | flipped };
+        let replies = after.legal_moves(Cell::Black).count_ones() as i32;
+        let parity = if bit & odd != 0 { 0 } else { 1 };
+        ordered.push((2 * replies + parity, bit, flipped));
+    }
+    ordered.sort_by_key(|&(key, _, _)| key);
+    ordered.into_iter().map(|(_, bit, flipped)| (bit, flipped)).collect()
+}
+
+// Every square on a completely filled line through it, for one line axis:
+// `lines` lists the masks of all lines along that axis.
+fn full_lines(occupied: u64, lines: &[u64]) -> u64 {
+    lines.iter().filter(|&&line| occupied & line == line).fold(0, |full, &line| full | line)
+}
+
+// Lower bound on the discs of `own` that can never be flipped.
+//
+// A disc is stable when, along each of the four axes, its line is full or it
+// touches the edge or another stable disc of its colour.
+fn stable_discs(own: u64, opp: u64) -> u64 {
+    let occupied = own | opp;
+    static LINES: OnceLock<[Vec<u64>; 4]> = OnceLock::new();
+    let lines = LINES.get_or_init(line_masks);
+    let full = [0, 1, 2, 3].map(|axis| full_lines(occupied, &lines[axis]));
+
+    // Squares anchored by the board edge along each axis.
+    let edge_h = FILE_A | FILE_H;
+    let edge_v = RANK_1 | RANK_8;
+    let edge_d = edge_h | edge_v;
+
+    let mut stable = 0;
+    loop {
+        let horizontal = full[0] | edge_h | ((stable << 1) & !FILE_A) | ((stable >> 1) & !FILE_H);
+        let vertical = full[1] | edge_v | (stable << 8) | (stable >> 8);
+        let diagonal = full[2] | edge_d | ((stable << 9) & !FILE_A) | ((stable >> 9) & !FILE_H);
+        let anti_diagonal = full[3] | edge_d | ((stable << 7) & !FILE_H) | ((stable >> 7) & !FILE_A);
+        let next = own & horizontal & vertical & diagonal & anti_diagonal;
+        if next == stable {
+            return stable;
+        }
+        stable = next;
+    }
+}
+
+// Masks of every row, column, diagonal and anti-diagonal, grouped by axis.
+fn line_masks() -> [Vec<u64>; 4] {
+    let mut rows = Vec::new();
+    let mut cols = Vec::new();
+    let mut diagonals = vec![0u64; 2 * BOARD_SIZE - 1];
+    let mut anti_diagonals = vec![0u64; 2 * BOARD_SIZE - 1];
+    for i in 0..BOARD_SIZE {
+        rows.push((0..BOARD_SIZE).fold(0, |mask, col| mask | square_bit(i, col)));
+        cols.push((0..BOARD_SIZE).fold(0, |mask, row| mask | square_bit(row, i)));
+    }
+    for row in 0..BOARD_SIZE {
+        for col in 0..BOARD_SIZE {
+            diagonals[row + BOARD_SIZE - 1 - col] |= square_bit(row, col);
+            anti_diagonals[row + col] |= square_bit(row, col);
+        }
+    }
+    [rows, cols, diagonals, anti_diagonals]
+}
@@ -0,0 +1,248 @@
+use crate::board::{Board, Move};
+use crate::cell::Cell;
+use crate::position::Position;
+
+/// One entry in the move history: who moved, what they played and which
+/// discs it flipped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ply {
+    pub player: Cell,
+    pub mv: Move,
+    /// Squares of the discs the move turned over, as (row, col). Empty for a pass.
+    pub flips: Vec<(usize, usize)>,
+}
+
+/// The final result of a finished game.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Win { winner: Cell, margin: usize },
+    Draw,
+}
+
+/// Something that happened as a side effect of playing a move.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// The given side had no legal move and passed automatically.
+    Passed(Cell),
+    /// Neither side can move any more.
+    GameOver(Outcome),
+}
+
+/// A game in progress: the board, whose turn it is and the moves so far.
+///
+/// Forced passes are applied automatically, so whenever the game is not over
+/// the side to move has at least one legal placement. Moves can be taken back
+/// with [`Game::undo`] and replayed with [`Game::redo`].
+#[derive(Clone, Debug)]
+pub struct Game {
+    // The position the game started from, before any forced pass.
+    start: Position,
+    board: Board,
+    current_player: Cell,
+    history: Vec<Ply>,
+    // Undone plies, most recently undone last.
+    redo_stack: Vec<Ply>,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Game::new()
+    }
+}
+
+impl Game {
+    /// Start a new game from the initial position with Black to move.
+    pub fn new() -> Self {
+        Game {
+            start: Position { board: Board::new(), to_move: Cell::Black },
+            board: Board::new(),
+            current_player: Cell::Black,
+            history: Vec::new(),
+            redo_stack: Vec::new(),
+        }
+    }
+
+    /// Start a new game on a `size` x `size` board, or `None` if the size is
+    /// not supported.
+    pub fn with_size(size: usize) -> Option<Self> {
+        Some(Game::from_position(Position { board: Board::with_size(size)?, to_move: Cell::Black }))
+    }
+
+    /// Start a game from an arbitrary position.
+    ///
+    /// If the side to move is blocked but the game is not over, the pass is
+    /// applied straight away.
+    pub fn from_position(position: Position) -> Self {
+        let mut game = Game {
+            start: position,
+            board: position.board,
+            current_player: position.to_move,
+            history: Vec::new(),
+            redo_stack: Vec::new(),
+        };
+        game.settle();
+        game
+    }
+
+    /// The position the game started from; replaying [`Game::history`] on
+    /// it reaches the current one.
+    pub fn start(&self) -> Position {
+        self.start
+    }
+
+    /// The current board and side to move.
+    pub fn position(&self) -> Position {
+        Position { board: self.board, to_move: self.current_player }
+    }
+
+    /// The current board.
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// The colour whose turn it is.
+    pub fn current_player(&self) -> Cell {
+        self.current_player
+    }
+
+    /// Every move played so far, including passes, oldest first.
+    pub fn history(&self) -> &[Ply] {
+        &self.history
+    }
+
+    /// Check if `mv` is legal for the side to move.
+    ///
+    /// A pass is only legal when the side to move has no other move.
+    pub fn is_legal(&self, mv: Move) -> bool {
+        match mv {
+            Move::Place { row, col } => self.board.is_valid_move(row, col, self.current_player),
+            Move::Pass => !self.is_over() && !self.board.has_valid_moves(self.current_player),
+        }
+    }
+
+    /// Play `mv` for the side to move and hand the turn over.
+    ///
+    /// Returns the passes that were forced as a result and, if the game has
+    /// ended, the outcome. Returns `None` and leaves the game untouched if the
+    /// move is illegal.
+    pub fn play(&mut self, mv: Move) -> Option<Vec<Event>> {
+        if !self.is_legal(mv) {
+            return None;
+        }
+        self.redo_stack.clear();
+        self.push(mv);
+        Some(self.settle())
+    }
+
+    /// Take back the last placement, together with any passes forced after it.
+    ///
+    /// Returns `false` if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        if self.history.iter().all(|ply| ply.mv == Move::Pass) {
+            return false;
+        }
+        // Passes are never chosen, so they are undone along with the move before them
+        while let Some(ply) = self.history.pop() {
+            let is_pass = ply.mv == Move::Pass;
+            if let Move::Place { row, col } = ply.mv {
+                self.board.undo_move(row, col, &ply.flips);
+            }
+            self.current_player = ply.player;
+            self.redo_stack.push(ply);
+            if !is_pass {
+                break;
+            }
+        }
+        true
+    }
+
+    /// Take back the last placement, then the computer's replies too, so it
+    /// is a human's turn again: moves are undone while `is_computer` picks
+    /// the side to move.
+    ///
+    /// Returns `false` if there is nothing to undo.
+    pub fn undo_turn(&mut self, is_computer: impl Fn(Cell) -> bool) -> bool {
+        let undone = self.undo();
+        while is_computer(self.current_player) && self.undo() {}
+        undone
+    }
+
+    /// Replay the last move taken back with [`Game::undo`].
+    ///
+    /// Returns the events of replaying it, or `None` if there is nothing to redo.
+    pub fn redo(&mut self) -> Option<Vec<Event>> {
+        let ply = self.redo_stack.pop()?;
+        self.push(ply.mv);
+        // Drop the passes that will be forced again, in the order they were undone
+        while self.redo_stack.last().is_some_and(|ply| ply.mv == Move::Pass) {
+            self.redo_stack.pop();
+        }
+        Some(self.settle())
+    }
+
+    // Apply `mv` for the side to move, record it and hand the turn over.
+    fn push(&mut self, mv: Move) {
+        let flips = match mv {
+            Move::Place { row, col } => self.board.apply_move(row, col, self.current_player),
+            Move::Pass => Vec::new(),
+        };
+        self.history.push(Ply { player: self.current_player, mv, flips });
+        self.current_player = self.current_player.opposite();
+    }
+
+    // Pass for the side to move while it is blocked but the game goes on,
+    // and report the outcome once neither side can move.
+    fn settle(&mut self) -> Vec<Event> {
+        let mut events = Vec::new();
+        if let Some(outcome) = self.outcome() {
+            events.push(Event::GameOver(outcome));
+        } else if !self.board.has_valid_moves(self.current_player) {
+            events.push(Event::Passed(self.current_player));
+            self.push(Move::Pass);
+        }
+        events
+    }
+
+    /// Check if an explicit pass by `player` agrees with the game, for
+    /// front ends that are sent passes. Passes are applied by the game
+    /// itself, so one is only accepted when the game has just passed for
+    /// `player`, or once the game is over.
+    pub fn accepts_pass(&self, player: Cell) -> bool {
+        self.is_over() || self.history.last().is_some_and(|ply| ply.player == player && ply.mv == Move::Pass)
+    }
+
+    /// The game is over when neither side has a legal move.
+    pub fn is_over(&self) -> bool {
+        !self.board.has_valid_moves(Cell::Black) && !self.board.has_valid_moves(Cell::White)
+    }
+
+    /// The result of the game, or `None` while it is still being played.
+    pub fn outcome(&self) -> Option<Outcome> {
+        if !self.is_over() {
+            return None;
+        }
+        Some(Outcome::of(&self.board))
+    }
+}
+
+impl Outcome {
+    /// The result by disc count on `board`, as if the game ended there.
+    pub fn of(board: &Board) -> Outcome {
+        let (black_count, white_count) = board.count_pieces();
+        match black_count.cmp(&white_count) {
+            std::cmp::Ordering::Greater => Outcome::Win { winner: Cell::Black, margin: black_count - white_count },
+            std::cmp::Ordering::Less => Outcome::Win { winner: Cell::White, margin: white_count - black_count },
+            std::cmp::Ordering::Equal => Outcome::Draw,
+        }
+    }
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Win { winner: Cell::Black, margin } => write!(f, "Black wins by {} points!", margin),
+            Outcome::Win { margin, .. } => write!(f, "White wins by {} points!", margin),
+            Outcome::Draw => write!(f, "Draw!"),
+        }
+    }
+}
@@ -0,0 +1,366 @@
+//! Game records in GGF, the Generic Game Format of the online Othello
+//! servers.
+//!
+//! A record is a list of tags between `(;` and `;)`:
+//!
+//! ```text
+//! (;GM[Othello]PC[GGS/os]DT[2003.12.15_13:24:03.MST]PB[alpha]PW[beta]
+//! RB[2034.85]RW[1990.02]TI[05:00//02:00]TY[8]RE[+6.000]
+//! BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *]
+//! B[f5//1.02]W[d6/-2.50/3.75]B[c3]...;)
+//! ```
+//!
+//! `BO` is the starting board, with `*` for black and `O` for white, then the
+//! side to move. Each `B` or `W` tag is a move, optionally followed by the
+//! mover's evaluation and the time it took, separated by `/`. Files usually
+//! hold many records one after another.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::board::{Board, Move};
+use crate::cell::Cell;
+use crate::game::Game;
+use crate::notation::{parse_square, square_name};
+use crate::position::Position;
+
+/// One side of a recorded game.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Player {
+    pub name: String,
+    pub rating: Option<f64>,
+}
+
+/// A move in a record, with what the player noted about it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RecordMove {
+    pub player: Cell,
+    pub mv: Move,
+    /// The mover's evaluation in discs, from its own point of view.
+    pub eval: Option<f64>,
+    /// Seconds spent on the move.
+    pub time: Option<f64>,
+}
+
+/// A game record: who played, under which conditions, the starting
+/// position and every move.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    /// Where the game was played (`PC`).
+    pub place: Option<String>,
+    /// When the game was played (`DT`).
+    pub date: Option<String>,
+    pub black: Player,
+    pub white: Player,
+    /// Time control such as `05:00//02:00` (`TI`).
+    pub time_control: Option<String>,
+    /// Board type such as `8` or `10` (`TY`).
+    pub board_type: Option<String>,
+    /// Result from Black's point of view, such as `+6.000` (`RE`).
+    pub result: Option<String>,
+    pub start: Position,
+    pub moves: Vec<RecordMove>,
+    /// Tags this module does not interpret, kept so they are written back.
+    pub other: Vec<(String, String)>,
+}
+
+/// Why a GGF record could not be read or replayed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GgfError {
+    /// There is no `(;` starting a record.
+    NoRecord,
+    /// The text ends inside a tag or before the closing `;)`.
+    Unterminated,
+    /// The record has no `BO` tag for the starting board.
+    MissingBoard,
+    /// The value of tag `tag` cannot be read.
+    BadValue { tag: String, value: String },
+    /// Move `index` (counting from 1) is not a square or a pass.
+    BadMove { index: usize, token: String },
+    /// Move `index` is not legal in the position reached so far.
+    IllegalMove { index: usize, token: String },
+}
+
+impl fmt::Display for GgfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GgfError::NoRecord => write!(f, "no GGF record found"),
+            GgfError::Unterminated => write!(f, "record is not terminated"),
+            GgfError::MissingBoard => write!(f, "record has no starting board"),
+            GgfError::BadValue { tag, value } => write!(f, "bad {} value '{}'", tag, value),
+            GgfError::BadMove { index, token } => write!(f, "move {}: '{}' is not a move", index, token),
+            GgfError::IllegalMove { index, token } => write!(f, "move {}: '{}' is illegal", index, token),
+        }
+    }
+}
+
+impl Error for GgfError {}
+
+impl Record {
+    /// A record of `game`, with its result once it is over. Players,
+    /// evaluations and times are left for the caller to fill in.
+    pub fn from_game(game: &Game) -> Record {
+        let result = game.outcome().map(|_| {
+            let (black_count, white_count) = game.board().count_pieces();
+            format!("{:+.3}", black_count as f64 - white_count as f64)
+        });
+        Record {
+            place: None,
+            date: None,
+            black: Player::default(),
+            white: Player::default(),
+            time_control: None,
+            board_type: Some(game.board().size().to_string()),
+            result,
+            start: game.start(),
+            moves: game.history().iter().map(|ply| RecordMove { player: ply.player, mv: ply.mv, eval: None, time: None }).collect(),
+            other: Vec::new(),
+        }
+    }
+
+    /// Replay the moves from the starting position.
+    ///
+    /// Passes may be written out or left implicit, but every placement must
+    /// be legal and by the side to move.
+    pub fn to_game(&self) -> Result<Game, GgfError> {
+        let mut game = Game::from_position(self.start);
+        for (i, record_move) in self.moves.iter().enumerate() {
+            let index = i + 1;
+            match record_move.mv {
+                Move::Pass => {
+                    // The game passes for a blocked side by itself
+                    if !game.accepts_pass(record_move.player) {
+                        return Err(GgfError::IllegalMove { index, token: "pa".to_string() });
+                    }
+                }
+                Move::Place { row, col } => {
+                    if record_move.player != game.current_player() || game.play(record_move.mv).is_none() {
+                        return Err(GgfError::IllegalMove { index, token: square_name(row, col) });
+                    }
+                }
+            }
+        }
+        Ok(game)
+    }
+}
+
+/// Read every record in `text`, such as a whole GGF file.
+pub fn parse_records(text: &str) -> Result<Vec<Record>, GgfError> {
+    let mut records = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("(;") {
+        let (tags, after) = read_tags(&rest[start + 2..])?;
+        records.push(from_tags(tags)?);
+        rest = after;
+    }
+    Ok(records)
+}
+
+impl FromStr for Record {
+    type Err = GgfError;
+
+    /// Read the first record in `s`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let start = s.find("(;").ok_or(GgfError::NoRecord)?;
+        let (tags, _) = read_tags(&s[start + 2..])?;
+        from_tags(tags)
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(;GM[Othello]")?;
+        let tags = [
+            ("PC", self.place.clone()),
+            ("DT", self.date.clone()),
+            ("PB", Some(self.black.name.clone()).filter(|name| !name.is_empty())),
+            ("PW", Some(self.white.name.clone()).filter(|name| !name.is_empty())),
+            ("RB", self.black.rating.map(|rating| format!("{:.2}", rating))),
+            ("RW", self.white.rating.map(|rating| format!("{:.2}", rating))),
+            ("TI", self.time_control.clone()),
+            ("TY", self.board_type.clone()),
+            ("RE", self.result.clone()),
+        ];
+        for (name, value) in tags {
+            if let Some(value) = value {
+                write_tag(f, name, &value)?;
+            }
+        }
+        for (name, value) in &self.other {
+            write_tag(f, name, value)?;
+        }
+        write_tag(f, "BO", &board_text(&self.start))?;
+        for record_move in &self.moves {
+            let name = if record_move.player == Cell::Black { "B" } else { "W" };
+            write_tag(f, name, &move_text(record_move))?;
+        }
+        write!(f, ";)")
+    }
+}
+
+// Write `name[value]`, escaping the characters that would end the value.
+fn write_tag(f: &mut fmt::Formatter<'_>, name: &str, value: &str) -> fmt::Result {
+    write!(f, "{}[", name)?;
+    for c in value.chars() {
+        if c == ']' || c == '\\' {
+            write!(f, "\\")?;
+        }
+        write!(f, "{}", c)?;
+    }
+    write!(f, "]")
+}
+
+// A board in `BO` form: the size, one word per row and the side to move.
+fn board_text(position: &Position) -> String {
+    let size = position.board.size();
+    let squares: String = position.board.to_string().chars().map(|c| if c == 'X' { '*' } else { c }).collect();
+    let rows: Vec<&str> = (0..size).map(|row| &squares[row * size..(row + 1) * size]).collect();
+    let side = if position.to_move == Cell::Black { '*' } else { 'O' };
+    format!("{} {} {}", size, rows.join(" "), side)
+}
+
+// A move in `B`/`W` form: `f5`, `f5/-2.00`, `f5//1.50` or `f5/-2.00/1.50`.
+fn move_text(record_move: &RecordMove) -> String {
+    let mut text = match record_move.mv {
+        Move::Place { row, col } => square_name(row, col),
+        Move::Pass => "pa".to_string(),
+    };
+    match (record_move.eval, record_move.time) {
+        (None, None) => {}
+        (eval, time) => {
+            let eval = eval.map(|eval| format!("{:.2}", eval)).unwrap_or_default();
+            let time = time.map(|time| format!("/{:.2}", time)).unwrap_or_default();
+            text = format!("{}/{}{}", text, eval, time);
+        }
+    }
+    text
+}
+
+// The (name, value) pairs of a record, in order.
+type Tags = Vec<(String, String)>;
+
+// Read the tags of a record from just after its `(;`, returning them and the
+// text after the closing `)`.
+fn read_tags(text: &str) -> Result<(Tags, &str), GgfError> {
+    let mut tags = Vec::new();
+    let mut name = String::new();
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '[' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '\\')) => value.extend(chars.next().map(|(_, c)| c)),
+                        Some((_, ']')) => break,
+                        Some((_, c)) => value.push(c),
+                        None => return Err(GgfError::Unterminated),
+                    }
+                }
+                tags.push((std::mem::take(&mut name), value));
+            }
+            ')' => return Ok((tags, &text[i + 1..])),
+            c if c.is_ascii_alphabetic() => name.push(c),
+            // Whitespace and the `;` before `)` separate tags
+            _ => name.clear(),
+        }
+    }
+    Err(GgfError::Unterminated)
+}
+
+// Build a record from its tags in order.
+fn from_tags(tags: Tags) -> Result<Record, GgfError> {
+    let mut record = Record {
+        place: None,
+        date: None,
+        black: Player::default(),
+        white: Player::default(),
+        time_control: None,
+        board_type: None,
+        result: None,
+        start: Position { board: Board::new(), to_move: Cell::Black },
+        moves: Vec::new(),
+        other: Vec::new(),
+    };
+    let mut has_board = false;
+    for (name, value) in tags {
+        let bad_value = || GgfError::BadValue { tag: name.clone(), value: value.clone() };
+        match name.as_str() {
+            // Every record names its game; anything but Othello cannot be replayed
+            "GM" if value.eq_ignore_ascii_case("othello") => {}
+            "GM" => return Err(bad_value()),
+            "PC" => record.place = Some(value),
+            "DT" => record.date = Some(value),
+            "PB" => record.black.name = value,
+            "PW" => record.white.name = value,
+            "RB" => record.black.rating = Some(value.trim().parse().map_err(|_| bad_value())?),
+            "RW" => record.white.rating = Some(value.trim().parse().map_err(|_| bad_value())?),
+            "TI" => record.time_control = Some(value),
+            "TY" => record.board_type = Some(value),
+            "RE" => record.result = Some(value),
+            "BO" => {
+                record.start = parse_board(&value).ok_or_else(bad_value)?;
+                has_board = true;
+            }
+            "B" | "W" => {
+                let player = if name == "B" { Cell::Black } else { Cell::White };
+                let index = record.moves.len() + 1;
+                let record_move = parse_move(&value, player).ok_or(GgfError::BadMove { index, token: value })?;
+                record.moves.push(record_move);
+            }
+            _ => record.other.push((name, value)),
+        }
+    }
+    if !has_board {
+        return Err(GgfError::MissingBoard);
+    }
+    Ok(record)
+}
+
+// Read a `BO` board: the size, the squares (optionally split into rows by
+// spaces), then `*` or `O` for the side to move.
+fn parse_board(text: &str) -> Option<Position> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let (size, squares, side) = match words.as_slice() {
+        [size, squares @ .., side] if !squares.is_empty() => (*size, squares.concat(), *side),
+        _ => return None,
+    };
+    let squares: String = squares.chars().map(|c| if c == '*' { 'X' } else { c }).collect();
+    let board: Board = squares.parse().ok()?;
+    if size.parse() != Ok(board.size()) {
+        return None;
+    }
+    let to_move = match side {
+        "*" => Cell::Black,
+        "O" => Cell::White,
+        _ => return None,
+    };
+    Some(Position { board, to_move })
+}
+
+// Read a move such as `f5`, `PA` or `d3/-1.50/0:03.2`.
+fn parse_move(text: &str, player: Cell) -> Option<RecordMove> {
+    let mut parts = text.split('/').map(str::trim);
+    let square = parts.next().unwrap_or_default();
+    let mv = if square.eq_ignore_ascii_case("pa") || square.eq_ignore_ascii_case("pass") {
+        Move::Pass
+    } else {
+        let (row, col) = parse_square(square)?;
+        Move::Place { row, col }
+    };
+    let eval = match parts.next() {
+        Some(eval) if !eval.is_empty() => Some(eval.parse().ok()?),
+        _ => None,
+    };
+    let time = match parts.next() {
+        Some(time) if !time.is_empty() => Some(parse_time(time)?),
+        _ => None,
+    };
+    Some(RecordMove { player, mv, eval, time })
+}
+
+// Seconds in a time written as `s`, `m:s` or `h:m:s`.
+fn parse_time(text: &str) -> Option<f64> {
+    text.split(':').try_fold(0.0, |total, part| Some(total * 60.0 + part.parse::<f64>().ok()?))
+}
@@ -0,0 +1,166 @@
+//! A Go Text Protocol dialect for driving the engine from GUIs and referees.
+//!
+//! Each command is one line, optionally preceded by a numeric id. Replies
+//! start with `=` on success or `?` on failure, repeat the id if one was
+//! given, and end with a blank line. Squares use standard notation (`f5`)
+//! and `pass`; colours are `black`/`b` or `white`/`w`.
+
+use std::io::{self, BufRead, Write};
+
+use crate::board::{Board, Move};
+use crate::cell::Cell;
+use crate::game::{Game, Outcome};
+use crate::notation::{parse_square, square_name};
+use crate::search::Engine;
+
+/// Commands this dialect understands, as listed by `list_commands`.
+pub const COMMANDS: [&str; 14] = [
+    "protocol_version",
+    "name",
+    "version",
+    "known_command",
+    "list_commands",
+    "boardsize",
+    "clear_board",
+    "play",
+    "genmove",
+    "undo",
+    "showboard",
+    "final_score",
+    "quit",
+    "exit",
+];
+
+/// Run a GTP session on a `size` x `size` board, reading commands from
+/// `input` until `quit` or the end of input and writing replies to `output`.
+/// `engine` plays `genmove`.
+pub fn run(input: impl BufRead, mut output: impl Write, engine: &mut dyn Engine, size: usize) -> io::Result<()> {
+    let mut game = Game::with_size(size).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "unsupported board size"))?;
+    for line in input.lines() {
+        let line = line?;
+        // Comments start with '#'; blank lines are ignored
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut words = line.split_whitespace().peekable();
+        let id = words.next_if(|word| word.bytes().all(|b| b.is_ascii_digit())).unwrap_or_default();
+        let command = words.next().unwrap_or_default();
+        let args: Vec<&str> = words.collect();
+
+        let reply = execute(&mut game, engine, command, &args);
+        match &reply {
+            Ok(text) => write!(output, "={} {}\n\n", id, text)?,
+            Err(text) => write!(output, "?{} {}\n\n", id, text)?,
+        }
+        output.flush()?;
+        if reply.is_ok() && (command == "quit" || command == "exit") {
+            break;
+        }
+    }
+    Ok(())
+}
+
+// Run one command, returning the text of a success or failure reply.
+fn execute(game: &mut Game, engine: &mut dyn Engine, command: &str, args: &[&str]) -> Result<String, String> {
+    match command {
+        "protocol_version" => Ok("2".to_string()),
+        "name" => Ok(env!("CARGO_PKG_NAME").to_string()),
+        "version" => Ok(env!("CARGO_PKG_VERSION").to_string()),
+        "known_command" => Ok(COMMANDS.contains(args.first().unwrap_or(&"")).to_string()),
+        "list_commands" => Ok(COMMANDS.join("\n")),
+        "boardsize" => {
+            let size = args.first().and_then(|size| size.parse().ok()).ok_or("boardsize not an integer")?;
+            *game = Game::with_size(size).ok_or("unacceptable size")?;
+            Ok(String::new())
+        }
+        "clear_board" => {
+            *game = Game::with_size(game.board().size()).expect("the current size is supported");
+            Ok(String::new())
+        }
+        "play" => {
+            let (color, vertex) = match args {
+                [color, vertex] => (parse_color(color)?, *vertex),
+                _ => return Err("syntax error".to_string()),
+            };
+            play(game, color, vertex)?;
+            Ok(String::new())
+        }
+        "genmove" => {
+            let color = parse_color(args.first().ok_or("syntax error")?)?;
+            if game.is_over() || !game.board().has_valid_moves(color) {
+                return Ok("pass".to_string());
+            }
+            if color != game.current_player() {
+                return Err("not this color's turn".to_string());
+            }
+            match engine.choose_move(game.board(), color) {
+                Move::Place { row, col } => {
+                    game.play(Move::Place { row, col }).ok_or("engine chose an illegal move")?;
+                    Ok(square_name(row, col))
+                }
+                Move::Pass => Ok("pass".to_string()),
+            }
+        }
+        "undo" => {
+            if game.undo() {
+                Ok(String::new())
+            } else {
+                Err("cannot undo".to_string())
+            }
+        }
+        "showboard" => Ok(format!("\n{}", board_text(game.board()))),
+        "final_score" => Ok(final_score(game)),
+        "quit" | "exit" => Ok(String::new()),
+        _ => Err("unknown command".to_string()),
+    }
+}
+
+fn parse_color(text: &str) -> Result<Cell, String> {
+    match text.to_ascii_lowercase().as_str() {
+        "b" | "black" => Ok(Cell::Black),
+        "w" | "white" => Ok(Cell::White),
+        _ => Err("invalid color".to_string()),
+    }
+}
+
+// Play `vertex` for `color`, where a pass must agree with the game.
+fn play(game: &mut Game, color: Cell, vertex: &str) -> Result<(), String> {
+    if vertex.eq_ignore_ascii_case("pass") {
+        return if game.accepts_pass(color) {
+            Ok(())
+        } else {
+            Err("illegal move".to_string())
+        };
+    }
+    let (row, col) = parse_square(vertex).ok_or("invalid coordinate")?;
+    if color != game.current_player() {
+        return Err("illegal move".to_string());
+    }
+    game.play(Move::Place { row, col }).ok_or("illegal move")?;
+    Ok(())
+}
+
+// The board with the same coordinates as vertices: letters for columns and
+// numbers from 1 for rows.
+fn board_text(board: &Board) -> String {
+    let size = board.size();
+    let labels: String = (0..size).map(|col| format!(" {}", (b'a' + col as u8) as char)).collect();
+    let mut lines = vec![format!("  {}", labels)];
+    for row in 0..size {
+        let cells: String = (0..size).map(|col| format!(" {}", board.get(row, col).to_char())).collect();
+        lines.push(format!("{:>2}{}", row + 1, cells));
+    }
+    lines.join("\n")
+}
+
+// Score in GTP form: `B+n`, `W+n` or `0` for a draw. A game still being
+// played is scored as it stands.
+fn final_score(game: &Game) -> String {
+    match game.outcome().unwrap_or_else(|| Outcome::of(game.board())) {
+        Outcome::Win { winner: Cell::Black, margin } => format!("B+{}", margin),
+        Outcome::Win { margin, .. } => format!("W+{}", margin),
+        Outcome::Draw => "0".to_string(),
+    }
+}
@@ -0,0 +1,121 @@
+//! Parsing of what players type at the prompt.
+//!
+//! A line is either a command, possibly followed by a file name or a count,
+//! or a square in RowCol (`dc`) or standard (`c4`) notation. Squares are
+//! checked against the game, so every way a line can be rejected is reported
+//! as an [`InputError`] rather than a panic.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::board::Move;
+use crate::cell::Cell;
+use crate::game::Game;
+use crate::notation::{parse_row_col, parse_square, square_name};
+
+/// Something the player asked for at the prompt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    /// Play a legal move for the side to move.
+    Play(Move),
+    Undo,
+    Redo,
+    /// Show the engine's best moves, this many of them.
+    Hint(usize),
+    /// Print the current position string.
+    Position,
+    /// Write the game to the named file.
+    Save(String),
+    /// Replace the game with the one in the named file.
+    Load(String),
+    Quit,
+}
+
+/// Why a line typed at the prompt was rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputError {
+    /// Nothing was typed.
+    Empty,
+    /// The line is neither a command nor shaped like a square.
+    UnknownCommand(String),
+    /// `save` or `load` was typed without a file name.
+    MissingFile(String),
+    /// The line names a square off the board, such as `i9` or `dz`.
+    OutOfRange(String),
+    /// The square at (row, col) already holds a disc.
+    Occupied { row: usize, col: usize },
+    /// A disc at (row, col) would not capture anything.
+    NoCapture { row: usize, col: usize },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Empty => write!(f, "Please enter a move or a command."),
+            InputError::UnknownCommand(text) => write!(f, "Unknown command '{}'.", text),
+            InputError::MissingFile(command) => write!(f, "'{}' needs a file name.", command),
+            InputError::OutOfRange(text) => write!(f, "'{}' is not on the board.", text),
+            InputError::Occupied { row, col } => write!(f, "{} is already occupied.", square_name(*row, *col)),
+            InputError::NoCapture { row, col } => write!(f, "{} does not capture any discs.", square_name(*row, *col)),
+        }
+    }
+}
+
+impl Error for InputError {}
+
+/// Parse one line of input for `game` and check any move against its rules.
+pub fn parse_input(text: &str, game: &Game) -> Result<Command, InputError> {
+    let text = text.trim();
+    match text.to_ascii_lowercase().as_str() {
+        "" => return Err(InputError::Empty),
+        "undo" => return Ok(Command::Undo),
+        "redo" => return Ok(Command::Redo),
+        "position" => return Ok(Command::Position),
+        "quit" | "exit" => return Ok(Command::Quit),
+        _ => {}
+    }
+    // A file name keeps its case and may contain spaces
+    let (word, argument) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    let argument = argument.trim();
+    match word.to_ascii_lowercase().as_str() {
+        "hint" if argument.is_empty() => return Ok(Command::Hint(1)),
+        "hint" => match argument.parse() {
+            Ok(count) if count > 0 => return Ok(Command::Hint(count)),
+            _ => return Err(InputError::UnknownCommand(text.to_string())),
+        },
+        "save" | "load" if argument.is_empty() => return Err(InputError::MissingFile(word.to_ascii_lowercase())),
+        "save" => return Ok(Command::Save(argument.to_string())),
+        "load" => return Ok(Command::Load(argument.to_string())),
+        _ => {}
+    }
+
+    let Some((row, col)) = parse_square(text).or_else(|| parse_row_col(text)) else {
+        return Err(if is_square_shaped(text) {
+            InputError::OutOfRange(text.to_string())
+        } else {
+            InputError::UnknownCommand(text.to_string())
+        });
+    };
+    if row >= game.board().size() || col >= game.board().size() {
+        return Err(InputError::OutOfRange(text.to_string()));
+    }
+    if game.board().get(row, col) != Cell::Empty {
+        return Err(InputError::Occupied { row, col });
+    }
+    if !game.is_legal(Move::Place { row, col }) {
+        return Err(InputError::NoCapture { row, col });
+    }
+    Ok(Command::Play(Move::Place { row, col }))
+}
+
+// Check if `text` is a letter followed by either a letter or a number, so
+// that notation only rejected it for naming a square off every board.
+fn is_square_shaped(text: &str) -> bool {
+    match text.as_bytes().split_first() {
+        Some((first, rest)) if first.is_ascii_alphabetic() => match rest {
+            [second] if second.is_ascii_alphabetic() => true,
+            _ => !rest.is_empty() && rest.iter().all(u8::is_ascii_digit),
+        },
+        _ => false,
+    }
+}
@@ -0,0 +1,45 @@
+//! Rules engine for Reversi (Othello).
+//!
+//! [`Board`] holds a position and knows the rules, [`Game`] tracks whose turn
+//! it is and applies forced passes on top of a board. [`Bitboard`] is a
+//! faster representation of the same rules. [`search`] holds the computer
+//! opponent, [`mcts`] a Monte Carlo alternative that needs no evaluation
+//! and [`endgame`] the exact solver for the last moves; the search and the
+//! solver cache results in a [`tt`] transposition table keyed by [`zobrist`]
+//! hashes, which [`smp`] shares between threads. [`notation`] reads and
+//! writes moves and game transcripts, [`ggf`] full game records, [`wthor`]
+//! the WTHOR game database, [`Position`] one-line positions and [`input`]
+//! what players type at the prompt. [`render`] draws boards in
+//! colour, [`tui`] is a full-screen alternative to the prompt and [`clock`]
+//! times games. [`gtp`] lets external programs drive the
+//! engine, and [`nboard`] lets the NBoard GUI use it for play and analysis.
+//! [`perft`] counts move-generator leaves for verification.
+
+pub mod bitboard;
+pub mod board;
+pub mod cell;
+pub mod clock;
+pub mod endgame;
+pub mod game;
+pub mod ggf;
+pub mod gtp;
+pub mod input;
+pub mod mcts;
+pub mod nboard;
+pub mod notation;
+pub mod perft;
+pub mod position;
+pub mod render;
+mod rng;
+pub mod search;
+pub mod smp;
+pub mod tt;
+pub mod tui;
+pub mod wthor;
+pub mod zobrist;
+
+pub use bitboard::Bitboard;
+pub use board::{Board, LegalMove, Move, BOARD_SIZE, MAX_SIZE, MIN_SIZE};
+pub use cell::Cell;
+pub use game::{Event, Game, Outcome, Ply};
+pub use position::Position;
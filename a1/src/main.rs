@@ -1,209 +1,694 @@
-use std::io::{self, Write, BufRead};
+use std::env;
+use std::fs;
+use std::io::{self, Write, BufRead, IsTerminal};
+use std::process;
+use std::time::{Duration, Instant};
 
-const BOARD_SIZE: usize = 8;
+use reversi::clock::{Clock, TimeControl};
+use reversi::endgame::{self, Solver};
+use reversi::ggf::{self, Player, Record};
+use reversi::mcts::{Budget, Mcts, Playout};
+use reversi::search::{AlphaBeta, Engine, Iteration, WIN_SCORE};
+use reversi::smp::LazySmp;
+use reversi::input::{self, Command};
+use reversi::render::{self, Theme, THEMES};
+use reversi::{gtp, nboard, notation, perft, tui, wthor, Board, Cell, Event, Game, Move, Position, BOARD_SIZE, MAX_SIZE, MIN_SIZE};
 
-// Define a Cell enum to represent the state of each cell on the board
-#[derive(Copy, Clone, PartialEq)]
-enum Cell {
-    Empty,
-    Black,
-    White,
+const USAGE: &str = "Usage: reversi [--computer black|white|both] [--engine alphabeta|mcts] [--depth N]
+               [--iterations N | --movetime MS] [--playout random|heavy] [--seed N]
+               [--size N] [--position POSITION] [--solve TRANSCRIPT|POSITION] [--gtp | --nboard]
+               [--load FILE] [--save FILE] [--replay FILE] [--wthor FILE.wtb|.jou|.trn] [--tui]
+               [--theme classic|colorblind|dark | --no-color] [--clock MAIN[+INC]|MAIN/PERIODxCOUNT]
+               [--verbose] [--threads N] [--bench] [--perft DEPTH | --divide DEPTH]";
+
+// Most empty squares `--solve` will take on; beyond this a solve takes hours
+const SOLVE_LIMIT: usize = 24;
+
+// Openings and middlegames searched by `--bench`, as transcripts
+const BENCH_GAMES: [&str; 8] = [
+    "f5f4g3e6c4g5d6d3e7b5",
+    "c4c3c2d6f6f5d7c7b7d3e2d8g6b3",
+    "d3c3e6d2c4f5c2b4g5c1b3b2a1d6c7f6f7g7",
+    "d3c5d6c3f4d7c4g3b2b3c6e6g4b7b4c2f7a2b5e3e2g5",
+    "f5f6e6f4f3d6f7g6h5g4d3g2h3h7c4e7h1f8g8f2e2d7d8c5e8h4",
+    "c4e3f3c5e2b4b6c3e6f7e7c6b3b2b7f6b5d6a2f8d3a7d7f4a3d2a1a5g6a8",
+    "d3e3f2c5f5f3d6g5b5c4g3e6h5g1e2e1f1a6d1h3a5g4f6d7g2g6f4b4h6b6d2a4h2c3",
+    "d3c3b3b2c4a3a1e6f6c2d6a2b1e2b4b5c5d7b6f4c8g6e7e8d2a6f2d1a4c7f7f5d8f8c6b8f3e3",
+];
+
+// Least time on the clock for which the computer solves the endgame exactly
+const SOLVE_TIME: Duration = Duration::from_secs(2);
+
+// Command-line settings for a session.
+struct Options {
+    computer_black: bool,
+    computer_white: bool,
+    depth: u32,
+    use_mcts: bool,
+    budget: Budget,
+    playout: Playout,
+    seed: u64,
+    // Transcript to replay before solving the position, e.g. "f5d6c3"
+    solve: Option<String>,
+    // Position to start the game from instead of the initial one
+    position: Option<Position>,
+    size: usize,
+    // Speak GTP on stdin/stdout instead of running the interactive prompt
+    gtp: bool,
+    // Speak the NBoard protocol on stdin/stdout instead
+    nboard: bool,
+    // GGF file to continue the first game of
+    load: Option<String>,
+    // GGF file to write the game to when the session ends
+    save: Option<String>,
+    // GGF file whose games are replayed and summarised
+    replay: Option<String>,
+    // WTHOR file to dump the games or names of
+    wthor: Option<String>,
+    // Play full screen instead of at the line prompt
+    tui: bool,
+    // Colours for the board, or `None` for plain text
+    theme: Option<&'static Theme>,
+    // Time each side gets, for a game on the clock
+    clock: Option<TimeControl>,
+    // Print every depth the search finishes to stderr
+    verbose: bool,
+    // Threads the alpha-beta search runs on
+    threads: usize,
+    // Time searches of fixed positions instead of playing
+    bench: bool,
+    // Count the leaves to this depth from the start, instead of playing
+    perft: Option<u32>,
+    // Split the perft count by first move
+    divide: bool,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options {
+            computer_black: false,
+            computer_white: false,
+            depth: 6,
+            use_mcts: false,
+            budget: Budget::Iterations(10_000),
+            playout: Playout::Random,
+            seed: 0,
+            solve: None,
+            position: None,
+            size: BOARD_SIZE,
+            gtp: false,
+            nboard: false,
+            load: None,
+            save: None,
+            replay: None,
+            wthor: None,
+            tui: false,
+            theme: Some(&THEMES[0]),
+            clock: None,
+            verbose: false,
+            threads: 1,
+            bench: false,
+            perft: None,
+            divide: false,
+        };
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--computer" => match args.next().as_deref() {
+                    Some("black") => options.computer_black = true,
+                    Some("white") => options.computer_white = true,
+                    Some("both") => {
+                        options.computer_black = true;
+                        options.computer_white = true;
+                    }
+                    _ => return Err("--computer expects black, white or both".to_string()),
+                },
+                "--depth" => {
+                    options.depth = match args.next().and_then(|depth| depth.parse().ok()) {
+                        Some(depth) if depth > 0 => depth,
+                        _ => return Err("--depth expects a positive number".to_string()),
+                    }
+                }
+                "--engine" => match args.next().as_deref() {
+                    Some("alphabeta") => options.use_mcts = false,
+                    Some("mcts") => options.use_mcts = true,
+                    _ => return Err("--engine expects alphabeta or mcts".to_string()),
+                },
+                "--iterations" => match args.next().and_then(|iterations| iterations.parse().ok()) {
+                    Some(iterations) if iterations > 0 => options.budget = Budget::Iterations(iterations),
+                    _ => return Err("--iterations expects a positive number".to_string()),
+                },
+                "--movetime" => match args.next().and_then(|millis| millis.parse().ok()) {
+                    Some(millis) if millis > 0 => options.budget = Budget::Time(Duration::from_millis(millis)),
+                    _ => return Err("--movetime expects a positive number of milliseconds".to_string()),
+                },
+                "--playout" => match args.next().as_deref() {
+                    Some("random") => options.playout = Playout::Random,
+                    Some("heavy") => options.playout = Playout::Heavy,
+                    _ => return Err("--playout expects random or heavy".to_string()),
+                },
+                "--seed" => match args.next().and_then(|seed| seed.parse().ok()) {
+                    Some(seed) => options.seed = seed,
+                    None => return Err("--seed expects a number".to_string()),
+                },
+                "--solve" => match args.next() {
+                    Some(game) => options.solve = Some(game),
+                    None => return Err("--solve expects a transcript or a position".to_string()),
+                },
+                "--gtp" => options.gtp = true,
+                "--nboard" => options.nboard = true,
+                "--tui" => options.tui = true,
+                "--verbose" => options.verbose = true,
+                "--bench" => options.bench = true,
+                "--perft" | "--divide" => match args.next().and_then(|depth| depth.parse().ok()) {
+                    Some(depth) => {
+                        options.perft = Some(depth);
+                        options.divide = arg == "--divide";
+                    }
+                    None => return Err(format!("{} expects a depth", arg)),
+                },
+                "--threads" => match args.next().and_then(|threads| threads.parse().ok()) {
+                    Some(threads) if threads > 0 => options.threads = threads,
+                    _ => return Err("--threads expects a positive number".to_string()),
+                },
+                "--theme" => match args.next().as_deref().and_then(Theme::by_name) {
+                    Some(theme) => options.theme = Some(theme),
+                    None => {
+                        let names: Vec<&str> = THEMES.iter().map(|theme| theme.name).collect();
+                        return Err(format!("--theme expects one of {}", names.join(", ")));
+                    }
+                },
+                "--no-color" => options.theme = None,
+                "--clock" => match args.next().map(|control| control.parse::<TimeControl>()) {
+                    Some(Ok(control)) => options.clock = Some(control),
+                    Some(Err(error)) => return Err(format!("invalid time control: {}", error)),
+                    None => return Err("--clock expects a time control such as 5m+3 or 10m/30x3".to_string()),
+                },
+                "--load" | "--save" | "--replay" | "--wthor" => {
+                    let path = args.next().ok_or_else(|| format!("{} expects a file", arg))?;
+                    match arg.as_str() {
+                        "--load" => options.load = Some(path),
+                        "--save" => options.save = Some(path),
+                        "--replay" => options.replay = Some(path),
+                        _ => options.wthor = Some(path),
+                    }
+                }
+                "--size" => match args.next().and_then(|size| size.parse().ok()) {
+                    Some(size) if Board::is_valid_size(size) => options.size = size,
+                    _ => return Err(format!("--size expects an even number from {} to {}", MIN_SIZE, MAX_SIZE)),
+                },
+                "--position" => match args.next().map(|position| position.parse::<Position>()) {
+                    Some(Ok(position)) => options.position = Some(position),
+                    Some(Err(error)) => return Err(format!("invalid position: {}", error)),
+                    None => return Err("--position expects a position".to_string()),
+                },
+                _ => return Err(format!("unknown argument '{}'", arg)),
+            }
+        }
+        if options.tui && options.clock.is_some() {
+            return Err("--clock is only available at the line prompt, not with --tui".to_string());
+        }
+        if options.tui && options.verbose {
+            return Err("--verbose is only available at the line prompt, not with --tui".to_string());
+        }
+        Ok(options)
+    }
+
+    fn is_computer(&self, color: Cell) -> bool {
+        match color {
+            Cell::Black => self.computer_black,
+            Cell::White => self.computer_white,
+            Cell::Empty => false,
+        }
+    }
+}
+
+// The search that plays the computer's moves. The solver is kept between
+// moves, and for hints, so its table carries over.
+enum Computer {
+    AlphaBeta { search: LazySmp, solver: Solver, verbose: bool },
+    Mcts(Mcts),
+}
+
+impl Computer {
+    fn new(options: &Options) -> Computer {
+        if options.use_mcts {
+            Computer::Mcts(Mcts::new(options.budget, options.playout, options.seed))
+        } else {
+            Computer::AlphaBeta { search: LazySmp::new(options.depth, options.threads), solver: Solver::new(), verbose: options.verbose }
+        }
+    }
 }
 
-impl Cell {
-    // Method to get the opposite color
-    fn opposite(&self) -> Cell {
+impl Engine for Computer {
+    fn choose_move(&mut self, board: &Board, color: Cell) -> Move {
         match self {
-            Cell::Black => Cell::White,
-            Cell::White => Cell::Black,
-            Cell::Empty => Cell::Empty, // Default case; shouldn't be used for valid moves
+            // Switch to the exact solver once the end is in reach
+            Computer::AlphaBeta { solver, .. } if endgame::within_reach(board) => solver.choose_move(board, color),
+            Computer::AlphaBeta { search, verbose, .. } => search.deepen(board, color, None, &mut |iteration| report(iteration, *verbose)).best_move,
+            Computer::Mcts(mcts) => mcts.choose_move(board, color),
         }
     }
+}
 
-    // Convert the cell to a character
-    fn to_char(&self) -> char {
+impl Computer {
+    // Choose a move in about `time`, for a game on the clock
+    fn choose_move_within(&mut self, board: &Board, color: Cell, time: Duration) -> Move {
         match self {
-            Cell::Empty => '.',
-            Cell::Black => 'B',
-            Cell::White => 'W',
+            Computer::AlphaBeta { solver, .. } if endgame::within_reach(board) && time >= SOLVE_TIME => {
+                solver.choose_move(board, color)
+            }
+            Computer::AlphaBeta { search, verbose, .. } => search.deepen(board, color, Some(time), &mut |iteration| report(iteration, *verbose)).best_move,
+            Computer::Mcts(mcts) => {
+                mcts.set_budget(Budget::Time(time));
+                mcts.choose_move(board, color)
+            }
         }
     }
 }
 
-// Define a Board struct to represent the game board
-struct Board {
-    grid: [[Cell; BOARD_SIZE]; BOARD_SIZE],
+// Print a finished search depth to stderr, if asked to.
+fn report(iteration: &Iteration, verbose: bool) {
+    if verbose {
+        eprintln!("{}", iteration);
+    }
+}
+
+// Index of `color`'s clock.
+fn side(color: Cell) -> usize {
+    (color == Cell::White) as usize
 }
 
-impl Board {
-    // Initialize a new board with starting positions
-    fn new() -> Self {
-        let mut grid = [[Cell::Empty; BOARD_SIZE]; BOARD_SIZE];
-        grid[3][3] = Cell::White;
-        grid[3][4] = Cell::Black;
-        grid[4][3] = Cell::Black;
-        grid[4][4] = Cell::White;
-        Board { grid }
+// Stop `player`'s clock, if the game is on the clock, and announce if the
+// player has run out of time. Returns `false` if the player lost on time.
+fn stop_clock(clocks: &mut Option<[Clock; 2]>, player: Cell) -> bool {
+    let in_time = clocks.as_mut().is_none_or(|clocks| clocks[side(player)].stop());
+    if !in_time {
+        println!("{} player has run out of time and loses.", player.to_char());
     }
+    in_time
+}
 
-    // Print the current state of the board
-    fn print(&self) {
-        println!("  abcdefgh");
-        for (i, row) in self.grid.iter().enumerate() {
-            print!("{} ", (b'a' + i as u8) as char);
-            for &cell in row.iter() {
-                print!("{}", cell.to_char());
-            }
-            println!();
+fn main() {
+    let mut options = Options::parse(env::args().skip(1)).unwrap_or_else(|message| {
+        eprintln!("{}\n{}", message, USAGE);
+        process::exit(2);
+    });
+    // Colour only a terminal, and never when the user has asked for none
+    if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) || !io::stdout().is_terminal() {
+        options.theme = None;
+    }
+    if options.bench {
+        bench(options.depth, options.threads);
+        return;
+    }
+    if let Some(depth) = options.perft {
+        // Not a `Game`, which would make a forced pass before counting began
+        let position = options.position.unwrap_or_else(|| Position {
+            board: Board::with_size(options.size).expect("size was checked when parsing options"),
+            to_move: Cell::Black,
+        });
+        run_perft(&position, depth, options.divide);
+        return;
+    }
+    if let Some(game) = &options.solve {
+        solve(game, options.size, options.theme);
+        return;
+    }
+    if let Some(path) = &options.replay {
+        replay(path, options.theme);
+        return;
+    }
+    if let Some(path) = &options.wthor {
+        dump_wthor(path);
+        return;
+    }
+
+    if options.nboard {
+        if let Err(error) = nboard::run(io::stdin().lock(), io::stdout().lock(), options.depth) {
+            eprintln!("NBoard session failed: {}", error);
+            process::exit(1);
         }
+        return;
     }
 
-    // Check if a move is valid for the given color
-    fn is_valid_move(&self, row: usize, col: usize, color: Cell) -> bool {
-        // Check if the position is out of bounds or already occupied
-        if row >= BOARD_SIZE || col >= BOARD_SIZE || self.grid[row][col] != Cell::Empty {
-            return false;
+    let mut engine = Computer::new(&options);
+    if options.gtp {
+        if let Err(error) = gtp::run(io::stdin().lock(), io::stdout().lock(), &mut engine, options.size) {
+            eprintln!("GTP session failed: {}", error);
+            process::exit(1);
         }
+        return;
+    }
 
-        // Define all possible directions to check
-        let directions = [
-            (-1, -1), (-1, 0), (-1, 1),
-            (0, -1),           (0, 1),
-            (1, -1), (1, 0), (1, 1),
-        ];
+    let mut game = match (&options.load, options.position) {
+        (Some(path), _) => load_record(path).unwrap_or_else(|message| {
+            eprintln!("{}", message);
+            process::exit(1);
+        }),
+        (None, Some(position)) => Game::from_position(position),
+        (None, None) => Game::with_size(options.size).expect("size was checked when parsing options"),
+    };
+    if options.tui {
+        game = tui::run(game, &mut engine, options.theme, |color| options.is_computer(color)).unwrap_or_else(|error| {
+            eprintln!("Cannot run the full-screen interface: {}", error);
+            process::exit(1);
+        });
+        save_on_exit(&game, &options);
+        return;
+    }
 
-        // Iterate over each direction to see if it's a valid capturing move
-        for &(dr, dc) in directions.iter() {
-            let mut r = row as isize + dr;
-            let mut c = col as isize + dc;
-            let mut found_opposite = false;
+    let stdin = io::stdin();
+    let mut clocks = options.clock.map(|control| [Clock::new(control); 2]);
 
-            while r >= 0 && r < BOARD_SIZE as isize && c >= 0 && c < BOARD_SIZE as isize {
-                match self.grid[r as usize][c as usize] {
-                    x if x == color.opposite() => found_opposite = true,
-                    x if x == color && found_opposite => return true, // Only valid if an opposite color is found first
-                    _ => break,
-                }
-                r += dr;
-                c += dc;
-            }
+    loop {
+        show(&game, options.theme);
+
+        // Check if the game has ended: both players have no valid move
+        if let Some(outcome) = game.outcome() {
+            println!("B player has no valid move.");
+            println!("W player has no valid move.");
+            println!("{}", outcome);
+            println!("Transcript: {}", notation::write_transcript(game.history()));
+            break;
         }
-        false
-    }
 
-    // Apply a move and update the board state
-    fn apply_move(&mut self, row: usize, col: usize, color: Cell) {
-        self.grid[row][col] = color;
+        // The side to move is on the clock from the moment its turn starts
+        let player = game.current_player();
+        if let Some(clocks) = &mut clocks {
+            clocks[side(player)].start();
+        }
 
-        let directions = [
-            (-1, -1), (-1, 0), (-1, 1),
-            (0, -1),           (0, 1),
-            (1, -1), (1, 0), (1, 1),
-        ];
+        // Let the computer choose its move
+        if options.is_computer(player) {
+            let mv = match &clocks {
+                Some(clocks) => engine.choose_move_within(game.board(), player, clocks[side(player)].allot(game.board().count_empty())),
+                None => engine.choose_move(game.board(), player),
+            };
+            if !stop_clock(&mut clocks, player) {
+                break;
+            }
+            if let Move::Place { row, col } = mv {
+                println!("Computer ({}) plays {}.", player.to_char(), notation::format_row_col(row, col));
+            }
+            announce(&game.play(mv).expect("search returned an illegal move"));
+            continue;
+        }
 
-        for &(dr, dc) in directions.iter() {
-            let mut r = row as isize + dr;
-            let mut c = col as isize + dc;
-            let mut to_flip = Vec::new();
+        // Get input move from the player
+        if let Some(clocks) = &clocks {
+            println!("Time left: B {}  W {}", clocks[side(Cell::Black)], clocks[side(Cell::White)]);
+        }
+        let mut input = String::new();
+        print!("Enter move for colour {} (RowCol or f5, undo, redo, hint [N], position, save FILE, load FILE, quit): ", player.to_char());
+        io::stdout().flush().expect("Failed to flush stdout.");
 
-            while r >= 0 && r < BOARD_SIZE as isize && c >= 0 && c < BOARD_SIZE as isize {
-                match self.grid[r as usize][c as usize] {
-                    x if x == color.opposite() => to_flip.push((r as usize, c as usize)),
-                    x if x == color => {
-                        for &(fr, fc) in to_flip.iter() {
-                            self.grid[fr][fc] = color; // Flip all in-between pieces to current color
-                        }
-                        break;
-                    }
-                    _ => break,
-                }
-                r += dr;
-                c += dc;
+        match stdin.lock().read_line(&mut input) {
+            // End of input: nobody is left to make a move
+            Ok(0) => {
+                println!();
+                break;
+            }
+            Ok(_) => {}
+            Err(error) => {
+                eprintln!("Failed to read input: {}", error);
+                break;
             }
         }
-    }
 
-    // Check if the player has any valid moves
-    fn has_valid_moves(&self, color: Cell) -> bool {
-        for row in 0..BOARD_SIZE {
-            for col in 0..BOARD_SIZE {
-                if self.is_valid_move(row, col, color) {
-                    return true;
+        if clocks.as_ref().is_some_and(|clocks| clocks[side(player)].is_flagged()) {
+            println!("{} player has run out of time and loses.", player.to_char());
+            break;
+        }
+        let command = input::parse_input(&input, &game);
+        // Anything that changes the game ends this side's time on the clock
+        if matches!(command, Ok(Command::Play(_) | Command::Undo | Command::Redo | Command::Load(_))) && !stop_clock(&mut clocks, player) {
+            break;
+        }
+
+        match command {
+            Ok(Command::Play(mv)) => announce(&game.play(mv).expect("parse_input only accepts legal moves")),
+            Ok(Command::Undo) => {
+                if !game.undo_turn(|color| options.is_computer(color)) {
+                    println!("Nothing to undo.");
                 }
             }
+            Ok(Command::Redo) => match game.redo() {
+                Some(events) => announce(&events),
+                None => println!("Nothing to redo."),
+            },
+            Ok(Command::Hint(count)) => match &mut engine {
+                Computer::AlphaBeta { solver, .. } => hint(&game, options.depth, count, solver),
+                Computer::Mcts(_) => hint(&game, options.depth, count, &mut Solver::new()),
+            },
+            Ok(Command::Position) => println!("{}", game.position()),
+            Ok(Command::Save(path)) => match save_record(&path, &game, &options) {
+                Ok(()) => println!("Saved the game to {}.", path),
+                Err(error) => println!("Cannot write {}: {}.", path, error),
+            },
+            Ok(Command::Load(path)) => match load_record(&path) {
+                Ok(loaded) => {
+                    game = loaded;
+                    println!("Loaded the game from {}.", path);
+                }
+                Err(message) => println!("{}", message),
+            },
+            Ok(Command::Quit) => break,
+            Err(error) => println!("{} Try again.", error),
         }
-        false
     }
 
-    // Count the number of black and white pieces on the board
-    fn count_pieces(&self) -> (usize, usize) {
-        let mut black_count = 0;
-        let mut white_count = 0;
-        for row in self.grid.iter() {
-            for &cell in row.iter() {
-                match cell {
-                    Cell::Black => black_count += 1,
-                    Cell::White => white_count += 1,
-                    _ => {}
-                }
-            }
+    save_on_exit(&game, &options);
+}
+
+// Write the game to the `--save` file, if one was given
+fn save_on_exit(game: &Game, options: &Options) {
+    if let Some(path) = &options.save {
+        match save_record(path, game, options) {
+            Ok(()) => println!("Saved the game to {}.", path),
+            Err(error) => eprintln!("Cannot write {}: {}.", path, error),
         }
-        (black_count, white_count)
     }
 }
 
-fn main() {
-    let mut board = Board::new();
-    let mut current_player = Cell::Black;
-    let stdin = io::stdin();
+// Print the `count` best moves for the side to move with their scores: exact
+// final disc differences from `solver` near the end, otherwise a `depth`-ply search
+fn hint(game: &Game, depth: u32, count: usize, solver: &mut Solver) {
+    let (board, player) = (game.board(), game.current_player());
+    let solved = endgame::within_reach(board);
+    let ranked = if solved {
+        println!("Best moves for {}, solved to the end:", player.to_char());
+        solver.rank_moves(board, player)
+    } else {
+        println!("Best moves for {}, searched {} moves ahead:", player.to_char(), depth);
+        AlphaBeta::new(depth).rank_moves(board, player)
+    };
+    for (i, result) in ranked.iter().take(count).enumerate() {
+        let Move::Place { row, col } = result.best_move else { continue };
+        let score = match result.score {
+            score if solved => format!("final score {:+}", score),
+            score if score > WIN_SCORE / 2 => format!("wins by {}", score - WIN_SCORE),
+            score if score < -WIN_SCORE / 2 => format!("loses by {}", -(score + WIN_SCORE)),
+            score => format!("score {:+}", score),
+        };
+        println!("{:>3}. {} ({})", i + 1, notation::square_name(row, col), score);
+    }
+}
 
-    loop {
-        board.print();
-        let (black_count, white_count) = board.count_pieces();
+// Print the board in `theme` with the legal moves marked, or as plain text
+fn show(game: &Game, theme: Option<&Theme>) {
+    match theme {
+        Some(theme) => print!("{}", render::grid(game.board(), (!game.is_over()).then_some(game.current_player()), theme)),
+        None => game.board().print(),
+    }
+}
 
-        // Check if the game has ended: both players have no valid move
-        if !board.has_valid_moves(Cell::Black) && !board.has_valid_moves(Cell::White) {
-            // Check if current player has any valid moves
-            if !board.has_valid_moves(current_player) {
-                println!("B player has no valid move.");
-                println!("W player has no valid move.");
-            }
-            // results
-            let result = match black_count.cmp(&white_count) {
-                std::cmp::Ordering::Greater => format!("Black wins by {} points!", black_count - white_count),
-                std::cmp::Ordering::Less => format!("White wins by {} points!", white_count - black_count),
-                std::cmp::Ordering::Equal => "Draw!".to_string(),
-            };
-            println!("{}", result);
-            break;
+// Announce forced passes; the end of the game is reported by the main loop
+fn announce(events: &[Event]) {
+    for event in events {
+        if let Event::Passed(player) = event {
+            println!("{} player has no valid move.", player.to_char());
         }
+    }
+}
 
-        // Get input move from the player
-        let mut input = String::new();
-        print!("Enter move for colour {} (RowCol): ", current_player.to_char());
-        io::stdout().flush().expect("Failed to flush stdout.");
+// Set up a game from a position string, or by replaying a transcript from the
+// start of a `size` x `size` board
+fn load_game(text: &str, size: usize) -> Result<Game, String> {
+    // Every move in a transcript has a row number; a position has no digits
+    if !text.chars().any(|c| c.is_ascii_digit()) {
+        let position = text.parse::<Position>().map_err(|error| format!("Invalid position: {}.", error))?;
+        Ok(Game::from_position(position))
+    } else {
+        let mut game = Game::with_size(size).expect("size was checked when parsing options");
+        notation::play_transcript(&mut game, text).map_err(|error| format!("Invalid transcript: {}.", error))?;
+        Ok(game)
+    }
+}
 
-        stdin.lock().read_line(&mut input).expect("Failed to read line");
-        let move_input = input.trim();
+// Set up the game, then print the exact best move and final score
+fn solve(text: &str, size: usize, theme: Option<&Theme>) {
+    let game = load_game(text, size).unwrap_or_else(|message| {
+        eprintln!("{}", message);
+        process::exit(1);
+    });
 
-        if move_input.len() != 2 {
-            println!("Invalid input. Try again.");
-            continue;
+    show(&game, theme);
+    if let Some(outcome) = game.outcome() {
+        println!("{}", outcome);
+        return;
+    }
+    if game.board().count_empty() > SOLVE_LIMIT {
+        eprintln!("Too many empty squares to solve ({} > {}).", game.board().count_empty(), SOLVE_LIMIT);
+        process::exit(1);
+    }
+    let player = game.current_player();
+    let result = Solver::new().solve_exact(game.board(), player);
+    if let Move::Place { row, col } = result.best_move {
+        println!("Best move for {}: {}", player.to_char(), notation::square_name(row, col));
+    }
+    println!("Final score: {} {:+} ({} nodes)", player.to_char(), result.score, result.nodes);
+}
+
+// Read the first game of a GGF file and replay it
+fn load_record(path: &str) -> Result<Game, String> {
+    let text = fs::read_to_string(path).map_err(|error| format!("Cannot read {}: {}.", path, error))?;
+    let record = text.parse::<Record>().map_err(|error| format!("Invalid GGF in {}: {}.", path, error))?;
+    record.to_game().map_err(|error| format!("Invalid game in {}: {}.", path, error))
+}
+
+// Write the game as a GGF record, naming each side by who played it
+fn save_record(path: &str, game: &Game, options: &Options) -> io::Result<()> {
+    let mut record = Record::from_game(game);
+    for (player, color) in [(&mut record.black, Cell::Black), (&mut record.white, Cell::White)] {
+        player.name = if options.is_computer(color) { env!("CARGO_PKG_NAME") } else { "human" }.to_string();
+    }
+    fs::write(path, format!("{}\n", record))
+}
+
+// Search every `BENCH_GAMES` position to `depth` on one thread and then on
+// `threads`, and print how long it took and how the speed scaled.
+fn bench(depth: u32, threads: usize) {
+    let counts = if threads > 1 { vec![1, threads] } else { vec![1] };
+    println!("Searching {} positions {} moves ahead.", BENCH_GAMES.len(), depth);
+    let mut totals = Vec::new();
+    for &count in &counts {
+        let (mut time, mut nodes) = (Duration::ZERO, 0);
+        for (i, text) in BENCH_GAMES.iter().enumerate() {
+            let game = notation::parse_transcript(text).expect("benchmark games are legal");
+            // A fresh search each time, so no position gains from the table of the last
+            let mut search = LazySmp::new(depth, count);
+            let start = Instant::now();
+            let result = search.deepen(game.board(), game.current_player(), None, &mut |_| {});
+            let took = start.elapsed();
+            println!("{} thread{} position {}: {:.3}s, {} nodes", count, if count == 1 { "" } else { "s" }, i + 1, took.as_secs_f64(), result.nodes);
+            time += took;
+            nodes += result.nodes;
         }
+        println!("{} thread{} total: {:.3}s, {} nodes, {:.0} nodes/s", count, if count == 1 { "" } else { "s" }, time.as_secs_f64(), nodes, nodes as f64 / time.as_secs_f64());
+        totals.push(time);
+    }
+    if let [single, parallel] = totals[..] {
+        println!("Speedup on {} threads: {:.2}x", threads, single.as_secs_f64() / parallel.as_secs_f64());
+    }
+}
 
-        let row = (move_input.chars().nth(0).unwrap() as usize) - ('a' as usize);
-        let col = (move_input.chars().nth(1).unwrap() as usize) - ('a' as usize);
+// Print the perft count at every depth up to `depth` with its speed, or
+// with `divide` the count below each first move at `depth` alone.
+fn run_perft(position: &Position, depth: u32, divide: bool) {
+    let (board, player) = (&position.board, position.to_move);
+    let rate = |nodes: u64, took: Duration| nodes as f64 / took.as_secs_f64().max(f64::EPSILON);
+    if divide {
+        let start = Instant::now();
+        let counts = perft::divide(board, player, depth);
+        let took = start.elapsed();
+        for &(mv, nodes) in &counts {
+            let name = match mv {
+                Move::Place { row, col } => notation::square_name(row, col),
+                Move::Pass => "pa".to_string(),
+            };
+            println!("{}: {}", name, nodes);
+        }
+        let total = if counts.is_empty() { perft::perft(board, player, depth) } else { counts.iter().map(|&(_, nodes)| nodes).sum() };
+        println!("Total: {} in {:.3}s, {:.0} nodes/s", total, took.as_secs_f64(), rate(total, took));
+        return;
+    }
+    for depth in 1..=depth {
+        let start = Instant::now();
+        let nodes = perft::perft(board, player, depth);
+        let took = start.elapsed();
+        println!("perft {}: {} in {:.3}s, {:.0} nodes/s", depth, nodes, took.as_secs_f64(), rate(nodes, took));
+    }
+}
 
-        // Check if the entered move is valid
-        if row >= BOARD_SIZE || col >= BOARD_SIZE || !board.is_valid_move(row, col, current_player) {
-            println!("Invalid move. Try again.");
-            continue;
+// Replay every game in a GGF file and print how each one ended
+fn replay(path: &str, theme: Option<&Theme>) {
+    let records = fs::read_to_string(path)
+        .map_err(|error| format!("Cannot read {}: {}.", path, error))
+        .and_then(|text| ggf::parse_records(&text).map_err(|error| format!("Invalid GGF in {}: {}.", path, error)))
+        .unwrap_or_else(|message| {
+            eprintln!("{}", message);
+            process::exit(1);
+        });
+
+    for (i, record) in records.iter().enumerate() {
+        println!("Game {}: {} (B) vs {} (W)", i + 1, player_name(&record.black), player_name(&record.white));
+        match record.to_game() {
+            Ok(game) => {
+                show(&game, theme);
+                match game.outcome() {
+                    Some(outcome) => println!("{}", outcome),
+                    None => println!("Unfinished."),
+                }
+                println!("Transcript: {}", notation::write_transcript(game.history()));
+            }
+            Err(error) => println!("Invalid game: {}.", error),
         }
+    }
+}
 
-        // Apply the move
-        board.apply_move(row, col, current_player);
+fn player_name(player: &Player) -> String {
+    if player.name.is_empty() {
+        return "?".to_string();
+    }
+    match player.rating {
+        Some(rating) => format!("{} [{:.0}]", player.name, rating),
+        None => player.name.clone(),
+    }
+}
+
+// Print the games of a WTHOR file as transcripts, one per line, or the names
+// in a player or tournament file with their numbers
+fn dump_wthor(path: &str) {
+    let data = fs::read(path).unwrap_or_else(|error| {
+        eprintln!("Cannot read {}: {}.", path, error);
+        process::exit(1);
+    });
+    let lower = path.to_ascii_lowercase();
+    let width = if lower.ends_with(".jou") {
+        Some(wthor::PLAYER_LEN)
+    } else if lower.ends_with(".trn") {
+        Some(wthor::TOURNAMENT_LEN)
+    } else {
+        None
+    };
 
-        // Switch to the other player
-        current_player = current_player.opposite();
+    let result = match width {
+        Some(width) => wthor::read_names(&data, width).map(|names| {
+            for (number, name) in names.iter().enumerate() {
+                println!("{} {}", number, name);
+            }
+        }),
+        None => wthor::read_games(&data).map(|games| {
+            for game in games {
+                match game {
+                    Ok(game) => println!("{}", notation::write_transcript(game.game.history())),
+                    Err(error) => eprintln!("Skipping invalid game: {}.", error),
+                }
+            }
+        }),
+    };
+    if let Err(error) = result {
+        eprintln!("Invalid WTHOR file {}: {}.", path, error);
+        process::exit(1);
     }
 }
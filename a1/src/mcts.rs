@@ -0,0 +1,256 @@
+//! Monte Carlo Tree Search (UCT) player.
+//!
+//! Needs no evaluation function: positions are scored by playing them out
+//! to the end, either uniformly at random or with a light positional policy.
+
+use std::time::{Duration, Instant};
+
+use crate::board::{Board, Move};
+use crate::cell::Cell;
+use crate::rng::Rng;
+use crate::search::Engine;
+
+/// How much thinking the search may do per move.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Budget {
+    /// A fixed number of playouts; the same seed gives the same move.
+    Iterations(u32),
+    /// As many playouts as fit in the given wall-clock time.
+    Time(Duration),
+}
+
+/// How moves are chosen during a playout.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Playout {
+    /// Every legal move is equally likely.
+    Random,
+    /// Prefer corners and edges and avoid squares next to empty corners.
+    Heavy,
+}
+
+/// The move chosen by an MCTS search.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MctsResult {
+    pub best_move: Move,
+    /// Fraction of playouts through `best_move` won by the side to move.
+    pub win_rate: f64,
+    pub iterations: u32,
+}
+
+// Exploration constant of the UCT formula.
+const EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+struct Node {
+    // The move leading here and the player who made it.
+    mv: Move,
+    player: Cell,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<Move>,
+    visits: u32,
+    // Sum of results for `player`: 1 per win, 0.5 per draw.
+    wins: f64,
+}
+
+/// A UCT search player with a seeded random generator.
+#[derive(Clone, Debug)]
+pub struct Mcts {
+    budget: Budget,
+    playout: Playout,
+    rng: Rng,
+}
+
+impl Mcts {
+    /// Create a player with the given budget and playout policy.
+    pub fn new(budget: Budget, playout: Playout, seed: u64) -> Self {
+        Mcts { budget, playout, rng: Rng::new(seed) }
+    }
+
+    /// Change how long each search runs, such as to fit a clock.
+    pub fn set_budget(&mut self, budget: Budget) {
+        self.budget = budget;
+    }
+
+    /// Find a move for `color` on `board`.
+    ///
+    /// Returns [`Move::Pass`] if `color` has no legal move.
+    pub fn search(&mut self, board: &Board, color: Cell) -> MctsResult {
+        let root_moves = moves(board, color);
+        if root_moves.len() <= 1 {
+            let best_move = root_moves.first().copied().unwrap_or(Move::Pass);
+            return MctsResult { best_move, win_rate: 0.5, iterations: 0 };
+        }
+
+        let mut nodes = vec![Node {
+            mv: Move::Pass,
+            player: color.opposite(),
+            parent: None,
+            children: Vec::new(),
+            untried: root_moves,
+            visits: 0,
+            wins: 0.0,
+        }];
+        let start = Instant::now();
+        let mut iterations = 0;
+        while !self.out_of_budget(iterations, start) {
+            self.iterate(&mut nodes, board);
+            iterations += 1;
+        }
+
+        let best = nodes[0]
+            .children
+            .iter()
+            .copied()
+            .max_by_key(|&child| nodes[child].visits)
+            .expect("root has children after one iteration");
+        let win_rate = nodes[best].wins / nodes[best].visits.max(1) as f64;
+        MctsResult { best_move: nodes[best].mv, win_rate, iterations }
+    }
+
+    fn out_of_budget(&self, iterations: u32, start: Instant) -> bool {
+        match self.budget {
+            // Always run at least one iteration so the root has a child
+            Budget::Iterations(limit) => iterations >= limit.max(1),
+            Budget::Time(limit) => iterations > 0 && start.elapsed() >= limit,
+        }
+    }
+
+    // One round of selection, expansion, playout and backpropagation.
+    fn iterate(&mut self, nodes: &mut Vec<Node>, root_board: &Board) {
+        let mut board = *root_board;
+        let mut current = 0;
+
+        // Selection: follow the best UCT child while the node is fully expanded
+        while nodes[current].untried.is_empty() && !nodes[current].children.is_empty() {
+            let parent_visits = nodes[current].visits as f64;
+            current = *nodes[current]
+                .children
+                .iter()
+                .max_by(|&&a, &&b| uct(&nodes[a], parent_visits).total_cmp(&uct(&nodes[b], parent_visits)))
+                .unwrap();
+            play(&mut board, nodes[current].mv, nodes[current].player);
+        }
+
+        // Expansion: add one untried move as a new child
+        if !nodes[current].untried.is_empty() {
+            let index = self.rng.below(nodes[current].untried.len());
+            let mv = nodes[current].untried.swap_remove(index);
+            let player = nodes[current].player.opposite();
+            play(&mut board, mv, player);
+            let untried = if board.has_valid_moves(Cell::Black) || board.has_valid_moves(Cell::White) {
+                moves(&board, player.opposite())
+            } else {
+                Vec::new()
+            };
+            nodes.push(Node { mv, player, parent: Some(current), children: Vec::new(), untried, visits: 0, wins: 0.0 });
+            let child = nodes.len() - 1;
+            nodes[current].children.push(child);
+            current = child;
+        }
+
+        // Playout from the new node, then backpropagate the result
+        let to_move = nodes[current].player.opposite();
+        let (black_count, white_count) = self.playout(&mut board, to_move);
+        let mut node = Some(current);
+        while let Some(index) = node {
+            let (own, other) = match nodes[index].player {
+                Cell::Black => (black_count, white_count),
+                _ => (white_count, black_count),
+            };
+            nodes[index].visits += 1;
+            nodes[index].wins += match own.cmp(&other) {
+                std::cmp::Ordering::Greater => 1.0,
+                std::cmp::Ordering::Equal => 0.5,
+                std::cmp::Ordering::Less => 0.0,
+            };
+            node = nodes[index].parent;
+        }
+    }
+
+    // Play the game out with the configured policy and return the final disc counts.
+    fn playout(&mut self, board: &mut Board, mut color: Cell) -> (usize, usize) {
+        let mut passed = false;
+        loop {
+            let legal = board.legal_moves(color);
+            if legal.is_empty() {
+                if passed {
+                    return board.count_pieces();
+                }
+                passed = true;
+                color = color.opposite();
+                continue;
+            }
+            passed = false;
+            let choice = match self.playout {
+                Playout::Random => &legal[self.rng.below(legal.len())],
+                Playout::Heavy => {
+                    // Best square weight, with random tie-breaking
+                    let mut best = 0;
+                    let mut best_key = (i32::MIN, 0);
+                    for (i, m) in legal.iter().enumerate() {
+                        let key = (self.weight(board, m.row, m.col), self.rng.next_u64());
+                        if key > best_key {
+                            best = i;
+                            best_key = key;
+                        }
+                    }
+                    &legal[best]
+                }
+            };
+            board.apply_move(choice.row, choice.col, color);
+            color = color.opposite();
+        }
+    }
+
+    // Weight of a square for heavy playouts; only the order matters. Corners
+    // are best, then edges, and the squares next to a corner are worst while
+    // that corner is still empty.
+    fn weight(&self, board: &Board, row: usize, col: usize) -> i32 {
+        let last = board.size() - 1;
+        // Distance to the nearest edge along each axis
+        let from_edge = (row.min(last - row), col.min(last - col));
+        let weight = match from_edge {
+            (0, 0) => 100,
+            (1, 1) => -50,
+            (0, 1) | (1, 0) => -20,
+            (0, _) | (_, 0) => 10,
+            _ => 0,
+        };
+        if weight < 0 {
+            let corner_row = if row < board.size() / 2 { 0 } else { last };
+            let corner_col = if col < board.size() / 2 { 0 } else { last };
+            if board.get(corner_row, corner_col) != Cell::Empty {
+                return 0;
+            }
+        }
+        weight
+    }
+}
+
+impl Engine for Mcts {
+    fn choose_move(&mut self, board: &Board, color: Cell) -> Move {
+        self.search(board, color).best_move
+    }
+}
+
+// Upper confidence bound of a child, from its parent's point of view.
+fn uct(node: &Node, parent_visits: f64) -> f64 {
+    let visits = node.visits as f64;
+    node.wins / visits + EXPLORATION * (parent_visits.ln() / visits).sqrt()
+}
+
+// Moves available to `color`: its legal placements, or a single pass.
+fn moves(board: &Board, color: Cell) -> Vec<Move> {
+    let legal: Vec<Move> = board.legal_moves(color).iter().map(|m| m.to_move()).collect();
+    if legal.is_empty() {
+        vec![Move::Pass]
+    } else {
+        legal
+    }
+}
+
+fn play(board: &mut Board, mv: Move, color: Cell) {
+    if let Move::Place { row, col } = mv {
+        board.apply_move(row, col, color);
+    }
+}
@@ -0,0 +1,140 @@
+//! The NBoard protocol, so the engine can play and analyse in the NBoard GUI.
+//!
+//! The GUI sends one command per line: `nboard` to start, `set game` with the
+//! game so far as a GGF record, `move` for each move played (including the
+//! engine's own), `go` when the engine is to move and `hint` to analyse the
+//! position. The engine answers `go` with `=== <move>/<eval>/<time>` and
+//! `hint n` with one `search` line for each of the `n` best moves. Squares are
+//! upper-case standard notation (`F5`) and a pass is `PA`. Evaluations are in
+//! discs: exact final disc differences once the end is solved or a win or
+//! loss is proven, and otherwise an estimate scaled from the heuristic
+//! evaluation. One search and one solver serve the whole session, so their
+//! transposition tables carry over from move to move.
+
+use std::io::{self, BufRead, Write};
+use std::time::Instant;
+
+use crate::board::Move;
+use crate::endgame::{within_reach, Solver};
+use crate::game::Game;
+use crate::ggf::Record;
+use crate::notation::{parse_square, square_name};
+use crate::search::{AlphaBeta, WIN_SCORE};
+
+// Heuristic points per disc of final margin, roughly: a corner is worth 25
+// points and, in practice, about eight discs at the end of the game.
+const HEURISTIC_PER_DISC: f64 = 3.0;
+
+/// Run an NBoard session, reading commands from `input` until `quit` or the
+/// end of input and writing replies to `output`. The search looks `depth`
+/// plies ahead until the GUI sets another depth.
+pub fn run(input: impl BufRead, mut output: impl Write, depth: u32) -> io::Result<()> {
+    let mut game = Game::new();
+    let mut depth = depth.max(1);
+    let mut search = AlphaBeta::new(depth);
+    let mut solver = Solver::new();
+    for line in input.lines() {
+        let line = line?;
+        let line = line.trim();
+        let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+        match command {
+            "nboard" => writeln!(output, "set myname {}", env!("CARGO_PKG_NAME"))?,
+            "set" => {
+                let (name, value) = args.split_once(' ').unwrap_or((args, ""));
+                match name {
+                    "depth" => match value.trim().parse() {
+                        Ok(value) if value > 0 => {
+                            depth = value;
+                            search.set_depth(depth);
+                        }
+                        _ => writeln!(output, "status invalid depth '{}'", value.trim())?,
+                    },
+                    "game" => match value.parse::<Record>().and_then(|record| record.to_game()) {
+                        Ok(value) => game = value,
+                        Err(message) => writeln!(output, "status invalid game: {}", message)?,
+                    },
+                    // Settings such as contempt do not apply to this engine
+                    _ => {}
+                }
+            }
+            "move" => {
+                if let Err(message) = play(&mut game, args) {
+                    writeln!(output, "status {}", message)?;
+                }
+            }
+            "go" => {
+                let start = Instant::now();
+                let (board, color) = (game.board(), game.current_player());
+                let (result, solved) = if within_reach(board) {
+                    (solver.solve_exact(board, color), true)
+                } else {
+                    (search.search(board, color), false)
+                };
+                let seconds = start.elapsed().as_secs_f64();
+                writeln!(output, "nodestats {} {:.2}", result.nodes, seconds)?;
+                writeln!(output, "=== {}/{:.2}/{:.2}", move_name(result.best_move), discs(result.score, solved), seconds)?;
+            }
+            "hint" => {
+                let count = args.trim().parse().unwrap_or(1);
+                writeln!(output, "status thinking")?;
+                let (board, color) = (game.board(), game.current_player());
+                // Solved scores are reported as searched to the end of the game
+                let (ranked, reached, solved) = if within_reach(board) {
+                    (solver.rank_moves(board, color), board.count_empty() as u32, true)
+                } else {
+                    (search.rank_moves(board, color), depth, false)
+                };
+                for result in ranked.iter().take(count) {
+                    writeln!(output, "search {} {:.2} 0 {}", move_name(result.best_move), discs(result.score, solved), reached)?;
+                }
+                writeln!(output, "status")?;
+            }
+            "learn" => writeln!(output, "learned")?,
+            "ping" => writeln!(output, "pong {}", args.trim())?,
+            "quit" => break,
+            // The protocol asks engines to ignore commands they do not know
+            _ => {}
+        }
+        output.flush()?;
+    }
+    Ok(())
+}
+
+// A score in discs. Solver scores already are the final disc difference, as
+// are proven wins and losses once WIN_SCORE is taken off; heuristic scores
+// are scaled down to an estimate.
+fn discs(score: i32, solved: bool) -> f64 {
+    if solved {
+        score as f64
+    } else if score > WIN_SCORE / 2 {
+        (score - WIN_SCORE) as f64
+    } else if score < -WIN_SCORE / 2 {
+        (score + WIN_SCORE) as f64
+    } else {
+        score as f64 / HEURISTIC_PER_DISC
+    }
+}
+
+fn move_name(mv: Move) -> String {
+    match mv {
+        Move::Place { row, col } => square_name(row, col).to_ascii_uppercase(),
+        Move::Pass => "PA".to_string(),
+    }
+}
+
+// Play a move given as `F5`, `PA` or with an evaluation and time attached
+// (`F5/-2.00/1.5`), where a pass must agree with the game.
+fn play(game: &mut Game, text: &str) -> Result<(), String> {
+    let square = text.split('/').next().unwrap_or_default().trim();
+    if square.eq_ignore_ascii_case("pa") {
+        // The side that passes is the one the game has just passed for
+        return if game.accepts_pass(game.current_player().opposite()) {
+            Ok(())
+        } else {
+            Err("illegal pass".to_string())
+        };
+    }
+    let (row, col) = parse_square(square).ok_or_else(|| format!("'{}' is not a square", square))?;
+    game.play(Move::Place { row, col }).ok_or_else(|| format!("illegal move {}", square))?;
+    Ok(())
+}
@@ -0,0 +1,143 @@
+//! Move notation and whole-game transcripts.
+//!
+//! The standard notation names a square by its column letter `a`–`h` and row
+//! digit `1`–`8`, so a game reads `f5d6c3d3...`. Larger boards continue the
+//! letters and numbers (`p16`). The interactive prompt also accepts the older
+//! row-letter, column-letter form (`dc` for row d, column c), and
+//! [`format_row_col`] keeps that form for display.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::board::{Move, MAX_SIZE};
+use crate::game::{Event, Game, Ply};
+
+/// Name of a square in standard notation, e.g. `f5`.
+pub fn square_name(row: usize, col: usize) -> String {
+    format!("{}{}", (b'a' + col as u8) as char, row + 1)
+}
+
+/// Name of a square as row letter then column letter, e.g. `dc`.
+pub fn format_row_col(row: usize, col: usize) -> String {
+    format!("{}{}", (b'a' + row as u8) as char, (b'a' + col as u8) as char)
+}
+
+/// Parse a square in standard notation (`f5`, case-insensitive) into (row, col).
+///
+/// Accepts any square that fits the largest board; whether it is on a given
+/// board is left to the caller.
+pub fn parse_square(text: &str) -> Option<(usize, usize)> {
+    let (&col, row) = text.as_bytes().split_first()?;
+    if !col.is_ascii_alphabetic() || row.is_empty() || !row.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let col = (col.to_ascii_lowercase() - b'a') as usize;
+    let row = text[1..].parse::<usize>().ok()?.checked_sub(1)?;
+    (row < MAX_SIZE && col < MAX_SIZE).then_some((row, col))
+}
+
+/// Parse a square as row letter then column letter (`dc`, case-insensitive)
+/// into (row, col).
+pub fn parse_row_col(text: &str) -> Option<(usize, usize)> {
+    match text.as_bytes() {
+        &[row, col] => {
+            let row = row.to_ascii_lowercase().checked_sub(b'a')? as usize;
+            let col = col.to_ascii_lowercase().checked_sub(b'a')? as usize;
+            (row < MAX_SIZE && col < MAX_SIZE).then_some((row, col))
+        }
+        _ => None,
+    }
+}
+
+/// Check if `text` is one of the ways transcripts write a pass.
+fn is_pass(text: &str) -> bool {
+    matches!(text, "pa" | "PA" | "ps" | "PS" | "--")
+}
+
+/// Write the moves of a game as a standard transcript such as `f5d6c3`.
+///
+/// Passes are written out as `pa`, so the transcript still reads correctly
+/// to programs that do not infer them.
+pub fn write_transcript(history: &[Ply]) -> String {
+    history
+        .iter()
+        .map(|ply| match ply.mv {
+            Move::Place { row, col } => square_name(row, col),
+            Move::Pass => "pa".to_string(),
+        })
+        .collect()
+}
+
+/// A transcript that could not be replayed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TranscriptError {
+    /// The token at `index` (counting from 1) is not a square or a pass.
+    BadSquare { index: usize, token: String },
+    /// The move at `index` is not legal in the position reached so far.
+    IllegalMove { index: usize, token: String },
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranscriptError::BadSquare { index, token } => write!(f, "move {}: '{}' is not a square", index, token),
+            TranscriptError::IllegalMove { index, token } => write!(f, "move {}: '{}' is illegal", index, token),
+        }
+    }
+}
+
+impl Error for TranscriptError {}
+
+/// Replay a transcript from the starting position of a standard board.
+///
+/// Squares may be upper or lower case and separated by whitespace. Passes
+/// may be written out as `pa`, `ps` or `--`, or left implicit.
+pub fn parse_transcript(text: &str) -> Result<Game, TranscriptError> {
+    let mut game = Game::new();
+    play_transcript(&mut game, text)?;
+    Ok(game)
+}
+
+/// Play the moves of a transcript on `game`, stopping at the first bad one.
+pub fn play_transcript(game: &mut Game, text: &str) -> Result<(), TranscriptError> {
+    // Set when the game has just passed for a side, so an explicit pass is allowed
+    let mut passed = game.history().last().is_some_and(|ply| ply.mv == Move::Pass);
+    for (i, token) in tokens(text).into_iter().enumerate() {
+        let index = i + 1;
+        if is_pass(&token) {
+            if !passed {
+                return Err(TranscriptError::IllegalMove { index, token });
+            }
+            passed = false;
+            continue;
+        }
+        let (row, col) = parse_square(&token).ok_or_else(|| TranscriptError::BadSquare { index, token: token.clone() })?;
+        let events = game.play(Move::Place { row, col }).ok_or(TranscriptError::IllegalMove { index, token })?;
+        passed = events.iter().any(|event| matches!(event, Event::Passed(_)));
+    }
+    Ok(())
+}
+
+// Split a transcript into moves: a letter followed by a row number or by a
+// second letter (`pa`), or `--`. Anything else becomes a one-character token.
+fn tokens(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().filter(|c| !c.is_whitespace()).peekable();
+    while let Some(c) = chars.next() {
+        let mut token = c.to_string();
+        match c {
+            '-' if chars.peek() == Some(&'-') => token.extend(chars.next()),
+            c if c.is_ascii_alphabetic() => match chars.peek() {
+                Some(next) if next.is_ascii_alphabetic() => token.extend(chars.next()),
+                _ => {
+                    while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                        token.push(digit);
+                    }
+                }
+            },
+            _ => {}
+        }
+        tokens.push(token);
+    }
+    tokens
+}
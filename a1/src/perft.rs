@@ -0,0 +1,76 @@
+//! Perft: counting the positions a given number of moves ahead, to check
+//! the move generator against known totals and to time it.
+//!
+//! Passes are counted the standard Othello way: a side with no legal move
+//! passes, and the pass takes up a ply like any other move. A game that
+//! ends before the depth is reached counts as one leaf.
+
+use crate::board::{Board, Move};
+use crate::cell::Cell;
+
+/// Number of leaves `depth` plies below `board` with `color` to move.
+pub fn perft(board: &Board, color: Cell, depth: u32) -> u64 {
+    let mut board = *board;
+    count(&mut board, color, depth)
+}
+
+/// The perft count below each move for `color`, in row-major order, with a
+/// single [`Move::Pass`] if `color` has to pass. The counts add up to
+/// [`perft`] at the same depth; a finished game or a `depth` of zero has no
+/// moves to divide between.
+pub fn divide(board: &Board, color: Cell, depth: u32) -> Vec<(Move, u64)> {
+    if depth == 0 {
+        return Vec::new();
+    }
+    let mut board = *board;
+    let moves = valid_moves(&board, color);
+    if moves.is_empty() {
+        if !board.has_valid_moves(color.opposite()) {
+            return Vec::new();
+        }
+        return vec![(Move::Pass, count(&mut board, color.opposite(), depth - 1))];
+    }
+    moves
+        .into_iter()
+        .map(|(row, col)| {
+            let flipped = board.apply_move(row, col, color);
+            let nodes = count(&mut board, color.opposite(), depth - 1);
+            board.undo_move(row, col, &flipped);
+            (Move::Place { row, col }, nodes)
+        })
+        .collect()
+}
+
+fn count(board: &mut Board, color: Cell, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let moves = valid_moves(board, color);
+    if moves.is_empty() {
+        if !board.has_valid_moves(color.opposite()) {
+            return 1;
+        }
+        return count(board, color.opposite(), depth - 1);
+    }
+    // Every move one ply from the end is a leaf, so there is no need to play it
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+    let mut nodes = 0;
+    for (row, col) in moves {
+        let flipped = board.apply_move(row, col, color);
+        nodes += count(board, color.opposite(), depth - 1);
+        board.undo_move(row, col, &flipped);
+    }
+    nodes
+}
+
+// Legal squares for `color`, found with `Board::is_valid_move` rather than
+// the bitboard so that it is the square-by-square rules being counted.
+fn valid_moves(board: &Board, color: Cell) -> Vec<(usize, usize)> {
+    let size = board.size();
+    (0..size)
+        .flat_map(|row| (0..size).map(move |col| (row, col)))
+        .filter(|&(row, col)| board.is_valid_move(row, col, color))
+        .collect()
+}
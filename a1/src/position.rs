@@ -0,0 +1,127 @@
+//! One-line position strings.
+//!
+//! A position is the 64 squares in row-major order, `X` for black, `O` for
+//! white and `-` for empty, then a space and the side to move, as in the
+//! common OBF convention (other board sizes simply have more or fewer
+//! squares):
+//!
+//! ```text
+//! ---------------------------OX------XO--------------------------- X
+//! ```
+//!
+//! A trailing `;` and anything after it (OBF move scores) is ignored.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::board::{Board, BOARD_SIZE, MAX_SIZE, MIN_SIZE};
+use crate::cell::Cell;
+use crate::notation::square_name;
+
+/// A board together with the side to move.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Position {
+    pub board: Board,
+    pub to_move: Cell,
+}
+
+/// Why a board or position string could not be parsed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParsePositionError {
+    /// The board part does not have one character per square of a
+    /// supported board size.
+    BadLength { found: usize },
+    /// The square at `index` (0 for a1, 63 for h8) is not `X`, `O` or `-`.
+    BadCell { index: usize, size: usize, found: char },
+    /// The side to move is missing.
+    MissingSide,
+    /// The side to move is not `X` or `O`.
+    BadSide { found: String },
+    /// There is more text after the side to move and before any `;`.
+    TrailingText { found: String },
+}
+
+impl fmt::Display for ParsePositionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsePositionError::BadLength { found } => {
+                write!(f, "expected {} squares (or the square of another even size from {} to {}), found {}", BOARD_SIZE * BOARD_SIZE, MIN_SIZE, MAX_SIZE, found)
+            }
+            ParsePositionError::BadCell { index, size, found } => {
+                let name = square_name(index / size, index % size);
+                write!(f, "square {} is '{}', expected 'X', 'O' or '-'", name, found)
+            }
+            ParsePositionError::MissingSide => write!(f, "missing side to move"),
+            ParsePositionError::BadSide { found } => write!(f, "side to move is '{}', expected 'X' or 'O'", found),
+            ParsePositionError::TrailingText { found } => write!(f, "unexpected '{}' after the side to move", found),
+        }
+    }
+}
+
+impl Error for ParsePositionError {}
+
+fn cell_char(cell: Cell) -> char {
+    match cell {
+        Cell::Black => 'X',
+        Cell::White => 'O',
+        Cell::Empty => '-',
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.size() {
+            for col in 0..self.size() {
+                write!(f, "{}", cell_char(self.get(row, col)))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Board {
+    type Err = ParsePositionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let found = s.chars().count();
+        let size = (MIN_SIZE..=MAX_SIZE).find(|size| size * size == found).unwrap_or(0);
+        let mut board = Board::empty_with_size(size).ok_or(ParsePositionError::BadLength { found })?;
+        for (index, c) in s.chars().enumerate() {
+            let cell = match c {
+                'X' => Cell::Black,
+                'O' => Cell::White,
+                '-' => Cell::Empty,
+                _ => return Err(ParsePositionError::BadCell { index, size, found: c }),
+            };
+            board.set(index / size, index % size, cell);
+        }
+        Ok(board)
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.board, cell_char(self.to_move))
+    }
+}
+
+impl FromStr for Position {
+    type Err = ParsePositionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.split(';').next().unwrap_or_default();
+        let mut parts = s.split_whitespace();
+        let board = parts.next().unwrap_or_default().parse()?;
+        let to_move = match parts.next() {
+            Some("X") => Cell::Black,
+            Some("O") => Cell::White,
+            Some(side) => return Err(ParsePositionError::BadSide { found: side.to_string() }),
+            None => return Err(ParsePositionError::MissingSide),
+        };
+        if let Some(extra) = parts.next() {
+            return Err(ParsePositionError::TrailingText { found: extra.to_string() });
+        }
+        Ok(Position { board, to_move })
+    }
+}
@@ -0,0 +1,74 @@
+//! Coloured boards for terminals that understand ANSI escape sequences.
+//!
+//! A [`Theme`] paints the board as felt with `●` for black discs, `○` for
+//! white discs and `·` on the legal moves of the side to move. The plain
+//! [`Board::grid_text`] remains the fallback for terminals without colour.
+
+use crate::board::Board;
+use crate::cell::Cell;
+
+/// Turns every colour and style back off.
+pub const RESET: &str = "\x1b[0m";
+
+// Restores the default foreground but keeps the background.
+const DEFAULT_FOREGROUND: &str = "\x1b[39m";
+
+/// Colours for drawing the board, as ANSI escape sequences.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub name: &'static str,
+    felt: &'static str,
+    black: &'static str,
+    white: &'static str,
+    dot: &'static str,
+}
+
+/// The themes to choose from; the first is the default.
+pub const THEMES: [Theme; 3] = [
+    // Green felt like a real board
+    Theme { name: "classic", felt: "\x1b[48;5;28m", black: "\x1b[38;5;16m", white: "\x1b[38;5;231m", dot: "\x1b[38;5;120m" },
+    // Blue and orange stay distinct with red-green colour blindness
+    Theme { name: "colorblind", felt: "\x1b[48;5;24m", black: "\x1b[38;5;16m", white: "\x1b[38;5;231m", dot: "\x1b[38;5;214m" },
+    // Low glare for dark terminals
+    Theme { name: "dark", felt: "\x1b[48;5;236m", black: "\x1b[38;5;208m", white: "\x1b[38;5;255m", dot: "\x1b[38;5;244m" },
+];
+
+impl Theme {
+    /// The theme called `name`, if there is one.
+    pub fn by_name(name: &str) -> Option<&'static Theme> {
+        THEMES.iter().find(|theme| theme.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The escape sequence that starts the board background.
+    pub fn felt(&self) -> &'static str {
+        self.felt
+    }
+
+    /// One square: a disc, a dot if it is a legal move, or blank felt. The
+    /// felt background is expected to be on already and is left on.
+    pub fn square(&self, cell: Cell, legal: bool) -> String {
+        match cell {
+            Cell::Black => format!("{}●{}", self.black, DEFAULT_FOREGROUND),
+            Cell::White => format!("{}○{}", self.white, DEFAULT_FOREGROUND),
+            Cell::Empty if legal => format!("{}·{}", self.dot, DEFAULT_FOREGROUND),
+            Cell::Empty => " ".to_string(),
+        }
+    }
+}
+
+/// The board laid out like [`Board::grid_text`] but drawn in `theme`, with
+/// the legal moves of `to_move` marked (pass `None` once the game is over).
+pub fn grid(board: &Board, to_move: Option<Cell>, theme: &Theme) -> String {
+    let size = board.size();
+    let labels: String = (0..size).map(|i| format!(" {}", (b'a' + i as u8) as char)).collect();
+    let mut text = format!("   {}\n", labels);
+    for row in 0..size {
+        text.push_str(&format!("{:>2} {}", row + 1, theme.felt));
+        for col in 0..size {
+            let legal = to_move.is_some_and(|color| board.is_valid_move(row, col, color));
+            text.push_str(&format!(" {}", theme.square(board.get(row, col), legal)));
+        }
+        text.push_str(&format!(" {}\n", RESET));
+    }
+    text
+}
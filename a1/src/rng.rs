@@ -0,0 +1,34 @@
+// Small seeded pseudo-random generator (SplitMix64), so that anything random
+// in the engine can be reproduced from a seed without extra dependencies.
+// Step added to the state for each number.
+pub(crate) const GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+// Scramble a state into an output. A `const fn` so that tables of random
+// numbers can be built at compile time.
+pub(crate) const fn mix(state: u64) -> u64 {
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(GAMMA);
+        mix(self.state)
+    }
+
+    // Uniform index in 0..len. `len` must not be zero.
+    pub(crate) fn below(&mut self, len: usize) -> usize {
+        (self.next_u64() % len as u64) as usize
+    }
+}
@@ -0,0 +1,475 @@
+//! Negamax alpha-beta search with a heuristic evaluation.
+//!
+//! [`AlphaBeta::deepen`] searches one ply deeper at a time with aspiration
+//! windows, reports the principal variation of every finished depth and
+//! can be stopped at a deadline or from another thread.
+//!
+//! Results are kept in a [`TranspositionTable`], so positions reached by
+//! different move orders are searched once and the best move from an
+//! earlier search is tried first.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::board::{Board, Move};
+use crate::cell::Cell;
+use crate::notation::square_name;
+use crate::tt::{Bound, TranspositionTable};
+
+/// Score of a won game before the final disc difference is added.
+pub const WIN_SCORE: i32 = 10_000;
+
+// Weights of the evaluation terms, per unit of difference.
+const MOBILITY_WEIGHT: i32 = 5;
+const CORNER_WEIGHT: i32 = 25;
+const DISC_WEIGHT: i32 = 1;
+
+// The four corner squares of `board`.
+fn corners(board: &Board) -> [(usize, usize); 4] {
+    let last = board.size() - 1;
+    [(0, 0), (0, last), (last, 0), (last, last)]
+}
+
+/// Anything that can choose a move, so front ends and protocols can drive
+/// any of the searches.
+pub trait Engine {
+    /// Choose a move for `color` on `board`, or [`Move::Pass`] if it has none.
+    fn choose_move(&mut self, board: &Board, color: Cell) -> Move;
+}
+
+/// The move chosen by a search and its score for the side to move.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SearchResult {
+    pub best_move: Move,
+    pub score: i32,
+    pub nodes: u64,
+}
+
+// Discs of `color` minus discs of its opponent, from the black and white
+// counts in `pieces`.
+pub(crate) fn disc_difference(pieces: (usize, usize), color: Cell) -> i32 {
+    let (black_count, white_count) = pieces;
+    let difference = black_count as i32 - white_count as i32;
+    if color == Cell::Black {
+        difference
+    } else {
+        -difference
+    }
+}
+
+/// Score of a finished game for `color`: a win or loss dominates every
+/// heuristic score, and larger margins are better.
+pub fn final_score(board: &Board, color: Cell) -> i32 {
+    let difference = disc_difference(board.count_pieces(), color);
+    match difference.signum() {
+        1 => WIN_SCORE + difference,
+        -1 => -WIN_SCORE + difference,
+        _ => 0,
+    }
+}
+
+/// Heuristic value of `board` for `color`, from mobility, corners and discs.
+pub fn evaluate(board: &Board, color: Cell) -> i32 {
+    let opponent = color.opposite();
+    let mobility = board.legal_moves(color).len() as i32 - board.legal_moves(opponent).len() as i32;
+    let corners: i32 = corners(board)
+        .iter()
+        .map(|&(row, col)| match board.get(row, col) {
+            x if x == color => 1,
+            x if x == opponent => -1,
+            _ => 0,
+        })
+        .sum();
+    MOBILITY_WEIGHT * mobility + CORNER_WEIGHT * corners + DISC_WEIGHT * disc_difference(board.count_pieces(), color)
+}
+
+// Half-width of the first aspiration window around the previous depth's
+// score; it doubles on the failing side until the score falls inside.
+const ASPIRATION_WINDOW: i32 = 25;
+
+// Nodes searched between checks of the stop flag and the deadline.
+const STOP_CHECK_INTERVAL: u64 = 1024;
+
+/// One finished depth of [`AlphaBeta::deepen`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Iteration {
+    pub depth: u32,
+    pub score: i32,
+    /// Nodes searched so far, counting every depth up to this one.
+    pub nodes: u64,
+    pub elapsed: Duration,
+    /// The principal variation: the moves both sides are expected to play,
+    /// starting with the best move. Cut short where the transposition table
+    /// already held the result.
+    pub pv: Vec<Move>,
+}
+
+impl Iteration {
+    /// Nodes searched per second.
+    pub fn nps(&self) -> u64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds > 0.0 {
+            (self.nodes as f64 / seconds) as u64
+        } else {
+            0
+        }
+    }
+}
+
+impl fmt::Display for Iteration {
+    /// One line, such as `depth 4 score +12 nodes 1530 nps 612000 pv f5 d6 c3 d3`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "depth {} score {:+} nodes {} nps {} pv", self.depth, self.score, self.nodes, self.nps())?;
+        for mv in &self.pv {
+            match *mv {
+                Move::Place { row, col } => write!(f, " {}", square_name(row, col))?,
+                Move::Pass => write!(f, " pa")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A negamax search with alpha-beta pruning, either to a fixed depth or
+/// deepening one ply at a time.
+#[derive(Clone, Debug)]
+pub struct AlphaBeta {
+    depth: u32,
+    nodes: u64,
+    // Shared with helper searches on other threads
+    tt: Arc<TranspositionTable>,
+    stop: Arc<AtomicBool>,
+    // When the search in progress must stop, if it has a hard time limit
+    deadline: Option<Instant>,
+    // Whether the search in progress watches the stop flag and deadline
+    stoppable: bool,
+    // Set once the search in progress has been cut short
+    stopped: bool,
+}
+
+impl AlphaBeta {
+    /// Create a search that looks `depth` plies ahead (at least one).
+    pub fn new(depth: u32) -> Self {
+        AlphaBeta {
+            depth: depth.max(1),
+            nodes: 0,
+            tt: Arc::new(TranspositionTable::new()),
+            stop: Arc::new(AtomicBool::new(false)),
+            deadline: None,
+            stoppable: false,
+            stopped: false,
+        }
+    }
+
+    /// Look `depth` plies ahead (at least one) from now on, keeping the
+    /// transposition table.
+    pub fn set_depth(&mut self, depth: u32) {
+        self.depth = depth.max(1);
+    }
+
+    /// A flag that stops [`AlphaBeta::deepen`] when set, from any thread.
+    ///
+    /// The search returns the deepest result it finished. The flag is
+    /// cleared when the next search starts.
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.stop)
+    }
+
+    /// A search to the same depth that shares this one's transposition
+    /// table but has its own stop flag, to run on another thread.
+    pub fn helper(&self) -> AlphaBeta {
+        AlphaBeta { stop: Arc::new(AtomicBool::new(false)), ..self.clone() }
+    }
+
+    /// Find the best move for `color` on `board`.
+    ///
+    /// Returns [`Move::Pass`] if `color` has no legal move.
+    pub fn search(&mut self, board: &Board, color: Cell) -> SearchResult {
+        self.nodes = 0;
+        // A fixed-depth search runs to the end, even after one that was cut short
+        self.deadline = None;
+        self.stoppable = false;
+        self.stopped = false;
+        self.tt.new_search();
+        let mut board = *board;
+        let mut pv = Vec::new();
+        let score = self.root(&mut board, color, -i32::MAX, i32::MAX, &mut pv);
+        SearchResult { best_move: pv.first().copied().unwrap_or(Move::Pass), score, nodes: self.nodes }
+    }
+
+    /// Search one ply deeper at a time, up to the depth set at creation, for
+    /// as long as the next depth is expected to finish within `time`, and
+    /// return the deepest result. A depth still running when `time` is up
+    /// is abandoned.
+    pub fn search_within(&mut self, board: &Board, color: Cell, time: Duration) -> SearchResult {
+        self.deepen(board, color, Some(time), &mut |_| {})
+    }
+
+    /// Search one ply deeper at a time up to the depth set at creation,
+    /// passing each finished depth to `report`, and return the deepest
+    /// result.
+    ///
+    /// Each depth after the first starts with a narrow window around the
+    /// previous score and widens it only if the score falls outside. With a
+    /// `time` limit, no depth starts that is not expected to finish in time
+    /// and a depth still running when the time is up is abandoned, as it is
+    /// when the [stop flag](AlphaBeta::stop_flag) is set.
+    pub fn deepen(&mut self, board: &Board, color: Cell, time: Option<Duration>, report: &mut dyn FnMut(&Iteration)) -> SearchResult {
+        self.tt.new_search();
+        self.lead(board, color, time, report)
+    }
+
+    // Start a new search in the transposition table, for `lead` and the
+    // helpers of a parallel search to share.
+    pub(crate) fn new_search(&self) {
+        self.tt.new_search();
+    }
+
+    // `deepen` without starting a new search in the table, as the main
+    // search of a parallel one whose table was already moved on.
+    pub(crate) fn lead(&mut self, board: &Board, color: Cell, time: Option<Duration>, report: &mut dyn FnMut(&Iteration)) -> SearchResult {
+        let start = Instant::now();
+        let max_depth = self.depth;
+        self.nodes = 0;
+        self.stop.store(false, Ordering::Relaxed);
+        // A time too far off to represent is no limit at all
+        self.deadline = time.and_then(|time| start.checked_add(time));
+        self.stopped = false;
+        let mut board = *board;
+        let mut previous = Duration::ZERO;
+        let mut result = None;
+        for depth in 1..=max_depth {
+            self.depth = depth;
+            // The first depth always finishes, so there is a move to play
+            self.stoppable = depth > 1;
+            let iteration = Instant::now();
+            let previous_score = result.as_ref().map(|result: &SearchResult| result.score);
+            let Some((score, pv)) = self.aspiration(&mut board, color, previous_score) else {
+                break;
+            };
+            let took = iteration.elapsed();
+            result = Some(SearchResult { best_move: pv.first().copied().unwrap_or(Move::Pass), score, nodes: self.nodes });
+            report(&Iteration { depth, score, nodes: self.nodes, elapsed: start.elapsed(), pv });
+            if let Some(time) = time {
+                // Each depth usually takes a few times as long as the one before
+                let growth = if previous.is_zero() { 4.0 } else { (took.as_secs_f64() / previous.as_secs_f64()).clamp(2.0, 10.0) };
+                if start.elapsed() + took.mul_f64(growth) > time {
+                    break;
+                }
+            }
+            if self.stop.load(Ordering::Relaxed) {
+                break;
+            }
+            previous = took;
+        }
+        self.depth = max_depth;
+        self.stoppable = false;
+        SearchResult { nodes: self.nodes, ..result.expect("the first depth always finishes") }
+    }
+
+    // Deepen like `deepen` but up to `skew` plies further at every depth,
+    // never past the full depth, filling the shared table for the main search
+    // until the stop flag is set, and return the nodes searched. The table's
+    // search number is left alone.
+    pub(crate) fn help(&mut self, board: &Board, color: Cell, skew: u32) -> u64 {
+        let max_depth = self.depth;
+        self.nodes = 0;
+        self.deadline = None;
+        self.stopped = false;
+        self.stoppable = true;
+        let mut board = *board;
+        let mut previous_score = None;
+        for depth in 1..=max_depth {
+            self.depth = (depth + skew).min(max_depth);
+            match self.aspiration(&mut board, color, previous_score) {
+                Some((score, _)) => previous_score = Some(score),
+                None => break,
+            }
+        }
+        self.depth = max_depth;
+        self.stoppable = false;
+        self.nodes
+    }
+
+    // Search the root to the current depth within a window around
+    // `previous`, widening it until the score lands inside. Returns the
+    // score and principal variation, or `None` if the search was stopped.
+    fn aspiration(&mut self, board: &mut Board, color: Cell, previous: Option<i32>) -> Option<(i32, Vec<Move>)> {
+        let (mut alpha, mut beta) = match previous {
+            // Won and lost scores jump by whole WIN_SCOREs, so a window is no use
+            Some(score) if score.abs() < WIN_SCORE / 2 => (score - ASPIRATION_WINDOW, score + ASPIRATION_WINDOW),
+            _ => (-i32::MAX, i32::MAX),
+        };
+        let mut delta = ASPIRATION_WINDOW;
+        loop {
+            let mut pv = Vec::new();
+            let score = self.root(board, color, alpha, beta, &mut pv);
+            if self.stopped {
+                return None;
+            }
+            if score <= alpha {
+                alpha = alpha.saturating_sub(delta).max(-i32::MAX);
+            } else if score >= beta {
+                beta = beta.saturating_add(delta);
+            } else {
+                return Some((score, pv));
+            }
+            delta = delta.saturating_mul(2);
+        }
+    }
+
+    /// Score every legal move for `color` on `board`, best first.
+    ///
+    /// Each move gets an exact score rather than just a bound, so this is
+    /// slower than [`AlphaBeta::search`].
+    pub fn rank_moves(&mut self, board: &Board, color: Cell) -> Vec<SearchResult> {
+        self.deadline = None;
+        self.stoppable = false;
+        self.stopped = false;
+        self.tt.new_search();
+        let mut board = *board;
+        let mut ranked = Vec::new();
+        for (row, col) in ordered_moves(&board, color) {
+            self.nodes = 0;
+            let flipped = board.apply_move(row, col, color);
+            let score = -self.negamax(&mut board, color.opposite(), self.depth - 1, -i32::MAX, i32::MAX, false, &mut Vec::new());
+            board.undo_move(row, col, &flipped);
+            ranked.push(SearchResult { best_move: Move::Place { row, col }, score, nodes: self.nodes });
+        }
+        ranked.sort_by_key(|result| -result.score);
+        ranked
+    }
+
+    // Score of `board` for `color` at the current depth, within (`alpha`,
+    // `beta`): at most `alpha` if every move fails low and at least `beta`
+    // on a cutoff. A score inside the window comes with its principal
+    // variation in `pv`; otherwise `pv` is left empty.
+    fn root(&mut self, board: &mut Board, color: Cell, mut alpha: i32, beta: i32, pv: &mut Vec<Move>) -> i32 {
+        let mut moves = ordered_moves(board, color);
+        if moves.is_empty() {
+            let mut line = Vec::new();
+            let score = -self.negamax(board, color.opposite(), self.depth - 1, -beta, -alpha, true, &mut line);
+            pv.push(Move::Pass);
+            pv.extend(line);
+            return score;
+        }
+        self.tt_move_first(board, color, &mut moves);
+        let original_alpha = alpha;
+        for (row, col) in moves {
+            let mut line = Vec::new();
+            let flipped = board.apply_move(row, col, color);
+            let score = -self.negamax(board, color.opposite(), self.depth - 1, -beta, -alpha, false, &mut line);
+            board.undo_move(row, col, &flipped);
+            if self.stopped {
+                return alpha;
+            }
+            if score > alpha {
+                alpha = score;
+                pv.clear();
+                pv.push(Move::Place { row, col });
+                pv.extend(line);
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+        if alpha > original_alpha && alpha < beta {
+            self.tt.store(board.key(color), self.depth, alpha, Bound::Exact, pv.first().copied());
+        }
+        alpha
+    }
+
+    // Score of `board` for `color`, with the line that leads to it in `pv`.
+    // `passed` is set when the previous ply was a pass, so a second pass in
+    // a row ends the game. The value does not depend on `passed`, so
+    // positions share table entries either way.
+    #[allow(clippy::too_many_arguments)]
+    fn negamax(&mut self, board: &mut Board, color: Cell, depth: u32, mut alpha: i32, beta: i32, passed: bool, pv: &mut Vec<Move>) -> i32 {
+        self.nodes += 1;
+        if self.stoppable && self.nodes.is_multiple_of(STOP_CHECK_INTERVAL) {
+            let timed_out = self.deadline.is_some_and(|deadline| Instant::now() >= deadline);
+            self.stopped |= timed_out || self.stop.load(Ordering::Relaxed);
+        }
+        if self.stopped {
+            return 0;
+        }
+        let key = board.key(color);
+        if depth > 0 {
+            if let Some(entry) = self.tt.probe(key) {
+                if entry.depth as u32 >= depth && entry.cuts_off(alpha, beta) {
+                    return entry.score;
+                }
+            }
+        }
+        let mut moves = ordered_moves(board, color);
+        if moves.is_empty() {
+            if passed {
+                return final_score(board, color);
+            }
+            let mut line = Vec::new();
+            let score = -self.negamax(board, color.opposite(), depth, -beta, -alpha, true, &mut line);
+            pv.push(Move::Pass);
+            pv.extend(line);
+            return score;
+        }
+        if depth == 0 {
+            return evaluate(board, color);
+        }
+        self.tt_move_first(board, color, &mut moves);
+        let original_alpha = alpha;
+        let mut best_move = None;
+        for (row, col) in moves {
+            let mut line = Vec::new();
+            let flipped = board.apply_move(row, col, color);
+            let score = -self.negamax(board, color.opposite(), depth - 1, -beta, -alpha, false, &mut line);
+            board.undo_move(row, col, &flipped);
+            if self.stopped {
+                return 0;
+            }
+            if score > alpha {
+                alpha = score;
+                best_move = Some(Move::Place { row, col });
+                pv.clear();
+                pv.push(Move::Place { row, col });
+                pv.extend(line);
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+        let bound = if alpha >= beta {
+            Bound::Lower
+        } else if alpha > original_alpha {
+            Bound::Exact
+        } else {
+            Bound::Upper
+        };
+        self.tt.store(key, depth, alpha, bound, best_move);
+        alpha
+    }
+
+    // Move the best move stored for `board` to the front of `moves`.
+    fn tt_move_first(&self, board: &Board, color: Cell, moves: &mut [(usize, usize)]) {
+        if let Some(Move::Place { row, col }) = self.tt.probe(board.key(color)).and_then(|entry| entry.best_move()) {
+            if let Some(index) = moves.iter().position(|&square| square == (row, col)) {
+                moves[..=index].rotate_right(1);
+            }
+        }
+    }
+}
+
+impl Engine for AlphaBeta {
+    fn choose_move(&mut self, board: &Board, color: Cell) -> Move {
+        self.deepen(board, color, None, &mut |_| {}).best_move
+    }
+}
+
+// Legal squares for `color`, corners first so they are searched early.
+fn ordered_moves(board: &Board, color: Cell) -> Vec<(usize, usize)> {
+    let mut moves: Vec<(usize, usize)> = board.legal_moves(color).iter().map(|m| (m.row, m.col)).collect();
+    let corners = corners(board);
+    moves.sort_by_key(|square| !corners.contains(square));
+    moves
+}
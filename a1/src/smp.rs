@@ -0,0 +1,77 @@
+//! Parallel search with Lazy SMP.
+//!
+//! Helper threads search the same position as the main search, sharing its
+//! lock-free transposition table and nothing else. What they store lets the
+//! main search cut off or order moves sooner; every other helper runs a ply
+//! ahead, up to the full depth, so the threads do not all repeat the same
+//! work. The main search alone decides the move, reports its progress and
+//! keeps time.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::board::{Board, Move};
+use crate::cell::Cell;
+use crate::search::{AlphaBeta, Engine, Iteration, SearchResult};
+
+/// An [`AlphaBeta`] search run on several threads.
+#[derive(Clone, Debug)]
+pub struct LazySmp {
+    search: AlphaBeta,
+    threads: usize,
+}
+
+impl LazySmp {
+    /// Create a search that looks `depth` plies ahead on `threads` threads
+    /// (at least one).
+    pub fn new(depth: u32, threads: usize) -> Self {
+        LazySmp { search: AlphaBeta::new(depth), threads: threads.max(1) }
+    }
+
+    /// Number of threads searching, counting the main one.
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// A flag that stops the search in progress when set; see
+    /// [`AlphaBeta::stop_flag`].
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        self.search.stop_flag()
+    }
+
+    /// Search like [`AlphaBeta::deepen`] with help from the other threads.
+    ///
+    /// Reported node counts are the main thread's; the result counts the
+    /// nodes of every thread.
+    pub fn deepen(&mut self, board: &Board, color: Cell, time: Option<Duration>, report: &mut dyn FnMut(&Iteration)) -> SearchResult {
+        if self.threads == 1 {
+            return self.search.deepen(board, color, time, report);
+        }
+        let helpers: Vec<AlphaBeta> = (1..self.threads).map(|_| self.search.helper()).collect();
+        let stops: Vec<Arc<AtomicBool>> = helpers.iter().map(AlphaBeta::stop_flag).collect();
+        // Before any helper stores anything, so no entry of this search looks stale
+        self.search.new_search();
+        let main = &mut self.search;
+        thread::scope(|scope| {
+            let handles: Vec<_> = helpers
+                .into_iter()
+                .enumerate()
+                .map(|(i, mut helper)| scope.spawn(move || helper.help(board, color, (i % 2 == 0) as u32)))
+                .collect();
+            let result = main.lead(board, color, time, report);
+            for stop in &stops {
+                stop.store(true, Ordering::Relaxed);
+            }
+            let helper_nodes: u64 = handles.into_iter().map(|handle| handle.join().expect("helper search panicked")).sum();
+            SearchResult { nodes: result.nodes + helper_nodes, ..result }
+        })
+    }
+}
+
+impl Engine for LazySmp {
+    fn choose_move(&mut self, board: &Board, color: Cell) -> Move {
+        self.deepen(board, color, None, &mut |_| {}).best_move
+    }
+}
@@ -0,0 +1,212 @@
+//! Transposition table: a fixed-size cache of search results keyed by the
+//! Zobrist key of a position.
+//!
+//! Each key maps to one slot. A new result replaces the one in its slot if
+//! it searched at least as deep, or if the old one is left over from an
+//! earlier search; otherwise the deeper, fresher result is kept.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+
+use crate::board::{Move, MAX_SIZE};
+
+/// Number of entries in a table created with [`TranspositionTable::new`].
+pub const DEFAULT_ENTRIES: usize = 1 << 18;
+
+/// How a stored score relates to the true value of the position.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Bound {
+    /// The score is the value.
+    Exact,
+    /// The value is at least the score: the search failed high.
+    Lower,
+    /// The value is at most the score: the search failed low.
+    Upper,
+}
+
+/// A stored search result.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub key: u64,
+    pub score: i32,
+    /// How far the search looked: plies for the midgame search, empty
+    /// squares for the endgame solver.
+    pub depth: u8,
+    pub bound: Bound,
+    // Search number that stored the entry, for ageing it out
+    generation: u8,
+    // Best move as row * MAX_SIZE + col
+    best: Option<u8>,
+}
+
+impl Entry {
+    /// The best move found, to be tried first next time.
+    pub fn best_move(&self) -> Option<Move> {
+        self.best.map(|square| Move::Place { row: square as usize / MAX_SIZE, col: square as usize % MAX_SIZE })
+    }
+
+    /// Check if the score settles the value within (`alpha`, `beta`), so the
+    /// position need not be searched again.
+    pub fn cuts_off(&self, alpha: i32, beta: i32) -> bool {
+        match self.bound {
+            Bound::Exact => true,
+            Bound::Lower => self.score >= beta,
+            Bound::Upper => self.score <= alpha,
+        }
+    }
+}
+
+/// A fixed-size table of search results, replacing by depth.
+///
+/// The table can be shared between threads without locking: each slot is
+/// two atomic words, the packed entry and the key XORed with it, so a slot
+/// torn by two threads writing at once no longer matches any key and reads
+/// as empty.
+pub struct TranspositionTable {
+    slots: Vec<[AtomicU64; 2]>,
+    // Slot count minus one; the count is a power of two
+    mask: u64,
+    generation: AtomicU8,
+}
+
+// Layout of an entry packed into a word: the score in the low 32 bits,
+// then the depth, the generation, the best square and the bound, with a
+// flag for whether there is a best square. Empty slots are all zero, and
+// the bound is never zero.
+const DEPTH_SHIFT: u32 = 32;
+const GENERATION_SHIFT: u32 = 40;
+const BEST_SHIFT: u32 = 48;
+const BOUND_SHIFT: u32 = 56;
+const HAS_BEST: u64 = 1 << 58;
+
+impl Entry {
+    fn pack(&self) -> u64 {
+        let bound: u64 = match self.bound {
+            Bound::Exact => 1,
+            Bound::Lower => 2,
+            Bound::Upper => 3,
+        };
+        let best = self.best.map_or(0, |square| (square as u64) << BEST_SHIFT | HAS_BEST);
+        self.score as u32 as u64
+            | (self.depth as u64) << DEPTH_SHIFT
+            | (self.generation as u64) << GENERATION_SHIFT
+            | best
+            | bound << BOUND_SHIFT
+    }
+
+    fn unpack(key: u64, data: u64) -> Option<Entry> {
+        let bound = match (data >> BOUND_SHIFT) & 3 {
+            1 => Bound::Exact,
+            2 => Bound::Lower,
+            3 => Bound::Upper,
+            _ => return None,
+        };
+        Some(Entry {
+            key,
+            score: data as u32 as i32,
+            depth: (data >> DEPTH_SHIFT) as u8,
+            bound,
+            generation: (data >> GENERATION_SHIFT) as u8,
+            best: (data & HAS_BEST != 0).then_some((data >> BEST_SHIFT) as u8),
+        })
+    }
+}
+
+impl TranspositionTable {
+    /// Create a table with [`DEFAULT_ENTRIES`] slots.
+    pub fn new() -> Self {
+        TranspositionTable::with_entries(DEFAULT_ENTRIES)
+    }
+
+    /// Create a table with room for `entries` results, rounded down to a
+    /// power of two (at least one).
+    pub fn with_entries(entries: usize) -> Self {
+        let slots = if entries <= 1 { 1 } else { 1 << entries.ilog2() };
+        TranspositionTable {
+            slots: (0..slots).map(|_| [AtomicU64::new(0), AtomicU64::new(0)]).collect(),
+            mask: slots as u64 - 1,
+            generation: AtomicU8::new(0),
+        }
+    }
+
+    /// Number of slots in the table.
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Start a new search: entries from earlier searches stay usable but
+    /// give way to anything stored from now on.
+    pub fn new_search(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Empty the table.
+    pub fn clear(&self) {
+        for slot in &self.slots {
+            slot[0].store(0, Ordering::Relaxed);
+            slot[1].store(0, Ordering::Relaxed);
+        }
+    }
+
+    // The entry in the slot for `key`, whatever position it belongs to.
+    fn load(&self, key: u64) -> Option<Entry> {
+        let slot = &self.slots[(key & self.mask) as usize];
+        let data = slot[1].load(Ordering::Relaxed);
+        let stored_key = slot[0].load(Ordering::Relaxed) ^ data;
+        Entry::unpack(stored_key, data)
+    }
+
+    /// The stored result for `key`, if its slot still holds it.
+    pub fn probe(&self, key: u64) -> Option<Entry> {
+        self.load(key).filter(|entry| entry.key == key)
+    }
+
+    /// Store a result for `key` searched to `depth`, unless its slot holds a
+    /// deeper result from this search. A deeper result for the same position
+    /// only takes `best_move` from the new one, and a `best_move` of `None`
+    /// keeps any best move already stored for the same position.
+    pub fn store(&self, key: u64, depth: u32, score: i32, bound: Bound, best_move: Option<Move>) {
+        let depth = depth.min(u8::MAX as u32) as u8;
+        let generation = self.generation.load(Ordering::Relaxed);
+        let best = best_move.and_then(|mv| match mv {
+            Move::Place { row, col } => Some((row * MAX_SIZE + col) as u8),
+            Move::Pass => None,
+        });
+        let mut entry = Entry { key, score, depth, bound, generation, best };
+        if let Some(old) = self.load(key) {
+            let deeper = old.generation == generation && old.depth > depth;
+            if old.key != key && deeper {
+                return;
+            }
+            if old.key == key && deeper {
+                // Keep the deeper result, with the newer best move if there is one
+                if best.is_none() || best == old.best {
+                    return;
+                }
+                entry = Entry { best, ..old };
+            } else if old.key == key && best.is_none() {
+                entry.best = old.best;
+            }
+        }
+        let data = entry.pack();
+        let slot = &self.slots[(key & self.mask) as usize];
+        slot[0].store(key ^ data, Ordering::Relaxed);
+        slot[1].store(data, Ordering::Relaxed);
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        TranspositionTable::new()
+    }
+}
+
+impl fmt::Debug for TranspositionTable {
+    // The slots are far too many to print.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TranspositionTable")
+            .field("capacity", &self.capacity())
+            .field("generation", &self.generation.load(Ordering::Relaxed))
+            .finish()
+    }
+}
@@ -0,0 +1,345 @@
+//! A full-screen terminal interface driven by the cursor keys.
+//!
+//! The board is drawn with standard notation labels, the legal moves of the
+//! side to move marked (`*` when drawn without a theme), the cursor in
+//! reverse video and the last move and the discs it flipped in bold. A side
+//! panel shows the score and the moves so far. Only ANSI escape sequences
+//! and `stty` are used, so it runs on any Unix terminal.
+
+use std::io::{self, BufRead, Write};
+use std::panic::{self, PanicHookInfo};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+
+use crate::board::Move;
+use crate::cell::Cell;
+use crate::game::{Event, Game};
+use crate::notation::square_name;
+use crate::render::{Theme, RESET};
+use crate::search::Engine;
+
+const REVERSE: &str = "\x1b[7m";
+const BOLD: &str = "\x1b[1m";
+const BOLD_UNDERLINE: &str = "\x1b[1;4m";
+
+const HELP: &str = "Arrows, wasd or hjkl move, Enter or space plays, ? hint, u undo, r redo, q quit.";
+
+// Run `stty` on the terminal and return what it printed.
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty").args(args).stdin(Stdio::inherit()).output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// Leave the alternate screen and put back the `stty -g` settings `saved`.
+fn restore(saved: &str) {
+    print!("\x1b[?25h\x1b[?1049l");
+    let _ = io::stdout().flush();
+    let _ = stty(&[saved]);
+}
+
+type PanicHook = dyn Fn(&PanicHookInfo<'_>) + Send + Sync + 'static;
+
+// The terminal in raw mode on the alternate screen; dropping it restores
+// the settings and the screen it found. A panic restores them too, before
+// its message is printed, so the message is not lost on the alternate
+// screen.
+struct Terminal {
+    saved: String,
+    previous_hook: Arc<PanicHook>,
+}
+
+impl Terminal {
+    fn enter() -> io::Result<Terminal> {
+        let saved = stty(&["-g"])?;
+        stty(&["raw", "-echo"])?;
+        print!("\x1b[?1049h\x1b[?25l");
+        io::stdout().flush()?;
+        let previous_hook: Arc<PanicHook> = Arc::from(panic::take_hook());
+        let (hook_saved, hook_previous) = (saved.clone(), Arc::clone(&previous_hook));
+        panic::set_hook(Box::new(move |info| {
+            restore(&hook_saved);
+            hook_previous(info);
+        }));
+        Ok(Terminal { saved, previous_hook })
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        // The hook cannot be swapped while unwinding, and has already run
+        if !std::thread::panicking() {
+            let previous = Arc::clone(&self.previous_hook);
+            panic::set_hook(Box::new(move |info| previous(info)));
+            restore(&self.saved);
+        }
+    }
+}
+
+// A key press that means something to the interface.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Play,
+    Hint,
+    Undo,
+    Redo,
+    Quit,
+}
+
+// Read keys until one the interface knows, or `None` at the end of input.
+fn read_key(input: impl BufRead) -> io::Result<Option<Key>> {
+    let mut bytes = input.bytes();
+    while let Some(byte) = bytes.next() {
+        let key = match byte? {
+            // Arrow keys arrive as ESC [ A to ESC [ D
+            0x1b => match (bytes.next().transpose()?, bytes.next().transpose()?) {
+                (Some(b'['), Some(b'A')) => Some(Key::Up),
+                (Some(b'['), Some(b'B')) => Some(Key::Down),
+                (Some(b'['), Some(b'C')) => Some(Key::Right),
+                (Some(b'['), Some(b'D')) => Some(Key::Left),
+                _ => None,
+            },
+            b'w' | b'k' => Some(Key::Up),
+            b's' | b'j' => Some(Key::Down),
+            b'd' | b'l' => Some(Key::Right),
+            b'a' | b'h' => Some(Key::Left),
+            b'\r' | b'\n' | b' ' => Some(Key::Play),
+            b'?' => Some(Key::Hint),
+            b'u' => Some(Key::Undo),
+            b'r' => Some(Key::Redo),
+            // Ctrl-C does not raise a signal in raw mode
+            b'q' | 0x03 => Some(Key::Quit),
+            _ => None,
+        };
+        if key.is_some() {
+            return Ok(key);
+        }
+    }
+    Ok(None)
+}
+
+/// Play `game` full screen until the player quits, letting `engine` move for
+/// the colours `is_computer` picks, and return the game as it was left. The
+/// board is drawn in `theme`, or with plain letters if there is none.
+///
+/// Fails if the terminal cannot be put into raw mode, for example when
+/// standard input is not a terminal.
+pub fn run(mut game: Game, engine: &mut dyn Engine, theme: Option<&Theme>, is_computer: impl Fn(Cell) -> bool) -> io::Result<Game> {
+    let _terminal = Terminal::enter()?;
+    let mut input = io::stdin().lock();
+    let mut output = io::stdout().lock();
+    let center = game.board().size() / 2;
+    let mut cursor = (center - 1, center - 1);
+    let mut message = String::new();
+
+    loop {
+        let player = game.current_player();
+        if let Some(outcome) = game.outcome() {
+            message = format!("{} Press u to take back a move or q to quit.", outcome);
+        } else if is_computer(player) {
+            write!(output, "{}", render(&game, theme, cursor, &format!("Computer ({}) is thinking...", player.to_char())))?;
+            output.flush()?;
+            let mv = engine.choose_move(game.board(), player);
+            let events = game.play(mv).expect("search returned an illegal move");
+            message = match mv {
+                Move::Place { row, col } => format!("Computer ({}) plays {}. {}", player.to_char(), square_name(row, col), passes(&events)).trim_end().to_string(),
+                Move::Pass => passes(&events),
+            };
+            continue;
+        }
+
+        write!(output, "{}", render(&game, theme, cursor, &message))?;
+        output.flush()?;
+        message.clear();
+        let size = game.board().size();
+        match read_key(&mut input)? {
+            Some(Key::Up) => cursor.0 = cursor.0.saturating_sub(1),
+            Some(Key::Down) => cursor.0 = (cursor.0 + 1).min(size - 1),
+            Some(Key::Left) => cursor.1 = cursor.1.saturating_sub(1),
+            Some(Key::Right) => cursor.1 = (cursor.1 + 1).min(size - 1),
+            Some(Key::Play) => {
+                let (row, col) = cursor;
+                match game.play(Move::Place { row, col }) {
+                    Some(events) => message = passes(&events),
+                    None => message = format!("{} is not a legal move.", square_name(row, col)),
+                }
+            }
+            Some(Key::Hint) if !game.is_over() => {
+                if let Move::Place { row, col } = engine.choose_move(game.board(), player) {
+                    cursor = (row, col);
+                    message = format!("Hint: {}.", square_name(row, col));
+                }
+            }
+            Some(Key::Hint) => {}
+            Some(Key::Undo) => {
+                if !game.undo_turn(&is_computer) {
+                    message = "Nothing to undo.".to_string();
+                }
+            }
+            Some(Key::Redo) => match game.redo() {
+                Some(events) => message = passes(&events),
+                None => message = "Nothing to redo.".to_string(),
+            },
+            Some(Key::Quit) | None => return Ok(game),
+        }
+    }
+}
+
+// Describe the forced passes among `events`.
+fn passes(events: &[Event]) -> String {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            Event::Passed(player) => Some(format!("{} has no valid move and passes.", player.to_char())),
+            Event::GameOver(_) => None,
+        })
+        .collect()
+}
+
+// Draw the whole screen: the board on the left, the score and moves on the
+// right, then the status and help lines. Lines end in "\r\n" for raw mode.
+fn render(game: &Game, theme: Option<&Theme>, cursor: (usize, usize), message: &str) -> String {
+    let board = game.board();
+    let size = board.size();
+    let player = game.current_player();
+    let over = game.is_over();
+
+    // The last placement and the discs it flipped, skipping any forced passes
+    let last = game.history().iter().rev().find(|ply| ply.mv != Move::Pass);
+    let last_square = last.and_then(|ply| match ply.mv {
+        Move::Place { row, col } => Some((row, col)),
+        Move::Pass => None,
+    });
+    let flipped = last.map(|ply| ply.flips.as_slice()).unwrap_or_default();
+
+    let mut lines = Vec::new();
+    let labels: String = (0..size).map(|col| format!(" {}", (b'a' + col as u8) as char)).collect();
+    lines.push(format!("   {} ", labels));
+    for row in 0..size {
+        // A style reset also ends the felt, so it is switched back on after each
+        let felt = theme.map(Theme::felt).unwrap_or_default();
+        let mut line = format!("{:>2} {}", row + 1, felt);
+        for col in 0..size {
+            let cell = board.get(row, col);
+            let legal = !over && board.is_valid_move(row, col, player);
+            let symbol = match (theme, cell) {
+                (Some(theme), cell) => theme.square(cell, legal),
+                (None, Cell::Empty) if legal => "*".to_string(),
+                (None, cell) => cell.to_char().to_string(),
+            };
+            let style = if (row, col) == cursor {
+                REVERSE
+            } else if Some((row, col)) == last_square {
+                BOLD_UNDERLINE
+            } else if flipped.contains(&(row, col)) {
+                BOLD
+            } else {
+                ""
+            };
+            line.push_str(&format!(" {}{}", style, symbol));
+            if !style.is_empty() {
+                line.push_str(RESET);
+                line.push_str(felt);
+            }
+        }
+        if theme.is_some() {
+            line.push_str(&format!(" {}", RESET));
+        }
+        lines.push(line);
+    }
+
+    let (black_count, white_count) = board.count_pieces();
+    let mut panel = vec![format!("Black (B) {:>3}", black_count), format!("White (W) {:>3}", white_count), String::new(), "Moves:".to_string()];
+    let moves: Vec<String> = game
+        .history()
+        .iter()
+        .map(|ply| match ply.mv {
+            Move::Place { row, col } => square_name(row, col),
+            Move::Pass => "pa".to_string(),
+        })
+        .collect();
+    let pairs: Vec<String> = moves.chunks(2).enumerate().map(|(i, pair)| format!("{:>3}. {}", i + 1, pair.join("  "))).collect();
+    // Keep the panel as tall as the board by showing only the latest moves
+    let room = (size + 1).saturating_sub(panel.len());
+    panel.extend(pairs[pairs.len().saturating_sub(room)..].iter().cloned());
+
+    let mut screen = "\x1b[H\x1b[2J".to_string();
+    let width = 3 + 2 * size + 1;
+    for i in 0..lines.len().max(panel.len()) {
+        let left = lines.get(i).map(String::as_str).unwrap_or_default();
+        // Escape codes take no room on screen, so pad by the visible width
+        let padding = " ".repeat(width.saturating_sub(visible_width(left)) + 4);
+        screen.push_str(&format!("{}{}{}\r\n", left, padding, panel.get(i).map(String::as_str).unwrap_or_default()));
+    }
+    screen.push_str("\r\n");
+    if !over {
+        screen.push_str(&format!("{} to move ({}).\r\n", if player == Cell::Black { "Black" } else { "White" }, square_name(cursor.0, cursor.1)));
+    }
+    screen.push_str(&format!("{}\r\n{}\r\n", message, HELP));
+    screen
+}
+
+// Width of `text` on screen, leaving out its escape sequences.
+fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut in_escape = false;
+    for c in text.chars() {
+        if c == '\x1b' {
+            in_escape = true;
+        } else if in_escape {
+            // Every sequence used here ends in 'm'
+            in_escape = c != 'm';
+        } else {
+            width += 1;
+        }
+    }
+    width
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(bytes: &[u8]) -> Vec<Key> {
+        let mut input = bytes;
+        std::iter::from_fn(|| read_key(&mut input).expect("reading a slice cannot fail")).collect()
+    }
+
+    #[test]
+    fn decodes_arrow_keys() {
+        assert_eq!(keys(b"\x1b[A\x1b[B\x1b[C\x1b[D"), [Key::Up, Key::Down, Key::Right, Key::Left]);
+        // Other escape sequences and unknown keys are skipped
+        assert_eq!(keys(b"\x1b[Zx\x1b[Aq"), [Key::Up, Key::Quit]);
+        assert_eq!(keys(b"k\r?u\x03"), [Key::Up, Key::Play, Key::Hint, Key::Undo, Key::Quit]);
+        assert_eq!(keys(b"\x1b"), []);
+    }
+
+    #[test]
+    fn width_leaves_out_escape_codes() {
+        assert_eq!(visible_width("abc"), 3);
+        assert_eq!(visible_width(&format!("{}B{} {}W{}", REVERSE, RESET, BOLD_UNDERLINE, RESET)), 3);
+        assert_eq!(visible_width(""), 0);
+    }
+
+    #[test]
+    fn marks_cursor_and_last_move() {
+        let mut game = Game::new();
+        game.play(Move::Place { row: 4, col: 5 }).expect("f5 is legal");
+        let screen = render(&game, None, (0, 0), "");
+        let lines: Vec<&str> = screen.split("\r\n").collect();
+        assert!(lines[0].starts_with("\x1b[H\x1b[2J    a b c d e f g h"), "{:?}", lines[0]);
+        assert!(lines[1].starts_with(&format!(" 1  {}.{}", REVERSE, RESET)), "{:?}", lines[1]);
+        // White's replies are starred; f5 was played and flipped e5
+        assert!(lines[4].starts_with(" 4  . . . W B * . ."), "{:?}", lines[4]);
+        let row5 = format!(" 5  . . . B {}B{} {}B{} . .", BOLD, RESET, BOLD_UNDERLINE, RESET);
+        assert_eq!(lines[5].trim_end(), row5);
+        assert!(screen.contains("White to move (a1)."), "{:?}", screen);
+        assert!(screen.contains("  1. f5"), "{:?}", screen);
+    }
+}
@@ -0,0 +1,226 @@
+//! The WTHOR binary database of tournament games.
+//!
+//! Every WTHOR file starts with a 16-byte header. A `.wtb` file then holds
+//! fixed-size game records: the tournament, black and white player numbers,
+//! Black's disc count, Black's theoretical score and 60 moves, one byte each
+//! as `10 * row + column` counting from 1 (`56` is f5), padded with zeros.
+//! Passes are not recorded. `.jou` and `.trn` files hold the player and
+//! tournament names those numbers refer to, as fixed-width NUL-padded text.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::board::{Move, BOARD_SIZE};
+use crate::cell::Cell;
+use crate::game::{Game, Outcome};
+use crate::notation::square_name;
+
+// Size of the header at the start of every file.
+const HEADER_LEN: usize = 16;
+
+// Size of a game record on an 8x8 board, and of the moves within it.
+const GAME_LEN: usize = 68;
+const MOVES_LEN: usize = 60;
+
+/// Width of a player name in a `.jou` file.
+pub const PLAYER_LEN: usize = 20;
+
+/// Width of a tournament name in a `.trn` file.
+pub const TOURNAMENT_LEN: usize = 26;
+
+/// The header shared by all WTHOR files.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    /// Date the file was written, as (year, month, day).
+    pub created: (u16, u8, u8),
+    /// Number of games in a `.wtb` file.
+    pub games: u32,
+    /// Number of names in a `.jou` or `.trn` file.
+    pub names: u16,
+    /// Year the games were played.
+    pub year: u16,
+    /// Board size, 0 or 8 for the standard board.
+    pub board_size: u8,
+    /// Empty squares from which the theoretical scores are exact.
+    pub depth: u8,
+}
+
+/// One validated game from a `.wtb` file.
+#[derive(Clone, Debug)]
+pub struct WthorGame {
+    /// Index into the `.trn` names.
+    pub tournament: u16,
+    /// Index into the `.jou` names.
+    pub black: u16,
+    /// Index into the `.jou` names.
+    pub white: u16,
+    /// Black's discs at the end, with empty squares going to the winner.
+    pub black_score: u8,
+    /// Black's discs at the end under perfect play from move `depth`.
+    pub theoretical_score: u8,
+    /// The game replayed from the starting position.
+    pub game: Game,
+}
+
+/// Why a WTHOR file or game could not be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WthorError {
+    /// The data ends before the header or record that should be there.
+    Truncated { expected: usize, found: usize },
+    /// The games are on a board other than 8x8.
+    UnsupportedBoard(u8),
+    /// Move `index` (counting from 1) of game `game` is not a square.
+    BadSquare { game: usize, index: usize, code: u8 },
+    /// Move `index` of game `game` is not legal in the position reached.
+    IllegalMove { game: usize, index: usize, square: String },
+    /// Black's recorded score in game `game` is not what the moves lead to.
+    ScoreMismatch { game: usize, recorded: u8, replayed: u8 },
+}
+
+impl fmt::Display for WthorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WthorError::Truncated { expected, found } => write!(f, "expected at least {} bytes, found {}", expected, found),
+            WthorError::UnsupportedBoard(size) => write!(f, "unsupported board size {}", size),
+            WthorError::BadSquare { game, index, code } => write!(f, "game {}, move {}: {} is not a square", game, index, code),
+            WthorError::IllegalMove { game, index, square } => write!(f, "game {}, move {}: {} is illegal", game, index, square),
+            WthorError::ScoreMismatch { game, recorded, replayed } => {
+                write!(f, "game {}: Black's score is recorded as {} but the moves give {}", game, recorded, replayed)
+            }
+        }
+    }
+}
+
+impl Error for WthorError {}
+
+fn u16_at(data: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([data[at], data[at + 1]])
+}
+
+/// Read the header at the start of `data`.
+pub fn read_header(data: &[u8]) -> Result<Header, WthorError> {
+    if data.len() < HEADER_LEN {
+        return Err(WthorError::Truncated { expected: HEADER_LEN, found: data.len() });
+    }
+    Ok(Header {
+        created: (data[0] as u16 * 100 + data[1] as u16, data[2], data[3]),
+        games: u32::from_le_bytes([data[4], data[5], data[6], data[7]]),
+        names: u16_at(data, 8),
+        year: u16_at(data, 10),
+        board_size: data[12],
+        depth: data[14],
+    })
+}
+
+/// The games of a `.wtb` file, replayed and checked one at a time.
+///
+/// A game with an illegal move, or whose recorded score does not match its
+/// final position, is reported as an error without stopping the games after
+/// it.
+#[derive(Clone, Debug)]
+pub struct Games<'a> {
+    header: Header,
+    records: std::slice::ChunksExact<'a, u8>,
+    // Number of the next game, counting from 1
+    number: usize,
+}
+
+impl Games<'_> {
+    /// The header of the file the games come from.
+    pub fn header(&self) -> Header {
+        self.header
+    }
+}
+
+impl Iterator for Games<'_> {
+    type Item = Result<WthorGame, WthorError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = self.records.next()?;
+        let number = self.number;
+        self.number += 1;
+        Some(read_game(record, number))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.records.size_hint()
+    }
+}
+
+/// Read the games of a `.wtb` file.
+///
+/// Fails if the header is missing, the board is not 8x8 or the file is
+/// shorter than the number of games in its header.
+pub fn read_games(data: &[u8]) -> Result<Games<'_>, WthorError> {
+    let header = read_header(data)?;
+    if header.board_size != 0 && header.board_size as usize != BOARD_SIZE {
+        return Err(WthorError::UnsupportedBoard(header.board_size));
+    }
+    let expected = HEADER_LEN + header.games as usize * GAME_LEN;
+    if data.len() < expected {
+        return Err(WthorError::Truncated { expected, found: data.len() });
+    }
+    Ok(Games { header, records: data[HEADER_LEN..expected].chunks_exact(GAME_LEN), number: 1 })
+}
+
+// Replay one game record; `number` identifies it in errors.
+fn read_game(record: &[u8], number: usize) -> Result<WthorGame, WthorError> {
+    let mut game = Game::new();
+    let moves = &record[GAME_LEN - MOVES_LEN..];
+    for (i, &code) in moves.iter().take_while(|&&code| code != 0).enumerate() {
+        let index = i + 1;
+        let (row, col) = ((code / 10) as usize, (code % 10) as usize);
+        if !(1..=BOARD_SIZE).contains(&row) || !(1..=BOARD_SIZE).contains(&col) {
+            return Err(WthorError::BadSquare { game: number, index, code });
+        }
+        let (row, col) = (row - 1, col - 1);
+        // Passes are left out of the record and applied by the game itself
+        if game.play(Move::Place { row, col }).is_none() {
+            return Err(WthorError::IllegalMove { game: number, index, square: square_name(row, col) });
+        }
+    }
+    let black_score = record[6];
+    let replayed = black_score_of(&game);
+    if black_score != replayed {
+        return Err(WthorError::ScoreMismatch { game: number, recorded: black_score, replayed });
+    }
+    Ok(WthorGame {
+        tournament: u16_at(record, 0),
+        black: u16_at(record, 2),
+        white: u16_at(record, 4),
+        black_score,
+        theoretical_score: record[7],
+        game,
+    })
+}
+
+// Black's discs on the final board, with the empty squares going to the
+// winner once the game is over and split evenly on a draw.
+fn black_score_of(game: &Game) -> u8 {
+    let (black, _) = game.board().count_pieces();
+    let empty = game.board().count_empty();
+    let score = match game.outcome() {
+        Some(Outcome::Win { winner: Cell::Black, .. }) => black + empty,
+        Some(Outcome::Draw) => black + empty / 2,
+        _ => black,
+    };
+    score as u8
+}
+
+/// Read the names in a `.jou` (`width` [`PLAYER_LEN`]) or `.trn` (`width`
+/// [`TOURNAMENT_LEN`]) file, indexed by the numbers the games use.
+pub fn read_names(data: &[u8], width: usize) -> Result<Vec<String>, WthorError> {
+    let header = read_header(data)?;
+    let expected = HEADER_LEN + header.names as usize * width;
+    if data.len() < expected {
+        return Err(WthorError::Truncated { expected, found: data.len() });
+    }
+    Ok(data[HEADER_LEN..expected]
+        .chunks_exact(width)
+        .map(|name| {
+            // Names are Latin-1, which maps byte for byte onto the first code points
+            let name = name.split(|&b| b == 0).next().unwrap_or_default();
+            name.iter().map(|&b| b as char).collect::<String>().trim_end().to_string()
+        })
+        .collect())
+}
@@ -0,0 +1,51 @@
+//! Zobrist keys: a random 64-bit number for each disc on each square, so a
+//! position hashes to the XOR of the numbers of its discs and the key can be
+//! updated move by move.
+
+use crate::board::{BOARD_SIZE, MAX_SIZE};
+use crate::cell::Cell;
+use crate::rng::{mix, GAMMA};
+
+// A key for each square of the largest board, for a black and a white disc.
+const KEYS: [[u64; 2]; MAX_SIZE * MAX_SIZE] = {
+    let mut keys = [[0; 2]; MAX_SIZE * MAX_SIZE];
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut square = 0;
+    while square < keys.len() {
+        let mut color = 0;
+        while color < 2 {
+            state = state.wrapping_add(GAMMA);
+            keys[square][color] = mix(state);
+            color += 1;
+        }
+        square += 1;
+    }
+    keys
+};
+
+/// XORed into a key when White is to move, so the same discs with a
+/// different side to move hash differently.
+pub const WHITE_TO_MOVE: u64 = 0x6a09_e667_f3bc_c908;
+
+/// Key of a `cell` disc on (`row`, `col`); an empty square contributes 0.
+pub fn square_key(row: usize, col: usize, cell: Cell) -> u64 {
+    match cell {
+        Cell::Black => KEYS[row * MAX_SIZE + col][0],
+        Cell::White => KEYS[row * MAX_SIZE + col][1],
+        Cell::Empty => 0,
+    }
+}
+
+/// Key of an 8x8 bitboard position from the point of view of the side to
+/// move, whose discs are `own`; the opponent's are `opp`.
+pub fn bitboard_key(own: u64, opp: u64) -> u64 {
+    let mut key = 0;
+    for (mut discs, color) in [(own, 0), (opp, 1)] {
+        while discs != 0 {
+            let square = discs.trailing_zeros() as usize;
+            discs &= discs - 1;
+            key ^= KEYS[(square / BOARD_SIZE) * MAX_SIZE + square % BOARD_SIZE][color];
+        }
+    }
+    key
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use reversi::{Bitboard, Board, Cell, BOARD_SIZE};
+
+use common::next;
+
+fn assert_same_moves(board: &Board, bitboard: &Bitboard, color: Cell) {
+    for row in 0..BOARD_SIZE {
+        for col in 0..BOARD_SIZE {
+            assert_eq!(
+                board.is_valid_move(row, col, color),
+                bitboard.is_valid_move(row, col, color),
+                "move ({}, {}) for {:?} on {:?}",
+                row,
+                col,
+                color,
+                bitboard
+            );
+        }
+    }
+    assert_eq!(board.has_valid_moves(color), bitboard.has_valid_moves(color));
+    assert_eq!(board.legal_moves(color).len() as u32, bitboard.legal_moves(color).count_ones());
+}
+
+#[test]
+fn initial_position_matches() {
+    let board = Board::new();
+    let bitboard = Bitboard::new();
+    assert_eq!(Bitboard::try_from(&board).unwrap(), bitboard);
+    assert_eq!(Board::from(&bitboard), board);
+    assert_eq!(bitboard.legal_moves(Cell::Black).count_ones(), 4);
+    assert_same_moves(&board, &bitboard, Cell::Black);
+    assert_same_moves(&board, &bitboard, Cell::White);
+}
+
+#[test]
+fn random_games_match_board() {
+    let mut state = 0x9e37_79b9_7f4a_7c15;
+    for _ in 0..200 {
+        let mut board = Board::new();
+        let mut bitboard = Bitboard::new();
+        let mut color = Cell::Black;
+        loop {
+            assert_same_moves(&board, &bitboard, Cell::Black);
+            assert_same_moves(&board, &bitboard, Cell::White);
+            assert_eq!(board.count_pieces(), bitboard.count_pieces());
+
+            let moves = bitboard.legal_moves(color);
+            if moves == 0 {
+                if !bitboard.has_valid_moves(color.opposite()) {
+                    break;
+                }
+                color = color.opposite();
+                continue;
+            }
+            let pick = next(&mut state) % moves.count_ones() as u64;
+            let mut remaining = moves;
+            for _ in 0..pick {
+                remaining &= remaining - 1;
+            }
+            let square = remaining.trailing_zeros() as usize;
+            let (row, col) = (square / BOARD_SIZE, square % BOARD_SIZE);
+
+            let before = bitboard;
+            let expected = board.legal_moves(color).into_iter().find(|m| (m.row, m.col) == (row, col)).unwrap();
+            board.apply_move(row, col, color);
+            let flipped = bitboard.apply_move(row, col, color);
+            assert_eq!(flipped.count_ones() as usize, expected.flips.len());
+            for &(r, c) in expected.flips.iter() {
+                assert_eq!(board.get(r, c), color);
+            }
+            assert_eq!(Bitboard::try_from(&board).unwrap(), bitboard, "after {:?} plays ({}, {}) on {:?}", color, row, col, before);
+            color = color.opposite();
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use reversi::clock::{Clock, TimeControl};
+
+fn secs(seconds: u64) -> Duration {
+    Duration::from_secs(seconds)
+}
+
+fn clock(control: &str) -> Clock {
+    Clock::new(control.parse().expect("test time controls are valid"))
+}
+
+#[test]
+fn parses_time_controls() {
+    assert_eq!("5m+3".parse(), Ok(TimeControl::Increment { main: secs(300), increment: secs(3) }));
+    assert_eq!("90".parse(), Ok(TimeControl::Increment { main: secs(90), increment: secs(0) }));
+    assert_eq!("10m/30x3".parse(), Ok(TimeControl::ByoYomi { main: secs(600), period: secs(30), periods: 3 }));
+    for bad in ["", "abc", "5m+", "-1", "5m/0x3", "5m/30x0", "5m/30", "1e300", "99999999999999999999m+0", "NaN"] {
+        let error = bad.parse::<TimeControl>().expect_err(bad);
+        assert_eq!(error.0, bad);
+    }
+}
+
+#[test]
+fn increment_is_added_after_each_move() {
+    let mut clock = clock("5m+3");
+    assert_eq!(clock.to_string(), "5:00");
+    assert!(clock.record(secs(10)));
+    assert_eq!(clock.to_string(), "4:53");
+    assert!(clock.record(secs(0)));
+    assert_eq!(clock.to_string(), "4:56");
+}
+
+#[test]
+fn flag_falls_when_main_time_runs_out() {
+    let mut clock = clock("10+5");
+    assert!(clock.record(secs(10)));
+    assert_eq!(clock.to_string(), "0:05.0");
+    assert!(!clock.record(secs(6)));
+    assert!(clock.is_flagged());
+    // A flagged clock stays flagged
+    assert!(!clock.record(secs(0)));
+    assert!(!clock.stop());
+}
+
+#[test]
+fn byo_yomi_periods_reset_and_run_out() {
+    let mut clock = clock("10/30x3");
+    assert_eq!(clock.to_string(), "0:10+3x0:30");
+    assert!(clock.record(secs(5)));
+    assert_eq!(clock.to_string(), "0:05.0+3x0:30");
+    // Running into byo-yomi within one period loses nothing
+    assert!(clock.record(secs(20)));
+    assert_eq!(clock.to_string(), "3x0:30");
+    // Each move gets a fresh period; exactly one period is still in time
+    assert!(clock.record(secs(30)));
+    assert_eq!(clock.to_string(), "3x0:30");
+    // Overrunning by two periods uses them up
+    assert!(clock.record(secs(65)));
+    assert_eq!(clock.to_string(), "1x0:30");
+    assert!(!clock.record(secs(31)));
+    assert!(clock.is_flagged());
+}
+
+#[test]
+fn allots_a_share_of_the_time_left() {
+    // A tenth of the main time for ten moves to go, plus most of the increment
+    assert_eq!(clock("60+2").allot(20), Duration::from_millis(7500));
+    // Never more than half of what is left
+    assert_eq!(clock("4+10").allot(2), secs(2));
+    // In byo-yomi most of a period can be spent
+    let mut byo_yomi = clock("0/30x2");
+    assert!(byo_yomi.record(secs(1)));
+    assert_eq!(byo_yomi.allot(20), secs(24));
+}
+
+#[test]
+fn huge_controls_do_not_overflow() {
+    let huge = "10000000000000000000+10000000000000000000";
+    let mut increment = clock(huge);
+    assert!(increment.allot(60) > secs(1_000_000));
+    assert!(increment.record(secs(1)));
+    assert!(increment.record(secs(1)));
+    assert!(increment.allot(1) > secs(1_000_000));
+    let mut byo_yomi = clock("10000000000000000000/10000000000000000000x3");
+    assert!(byo_yomi.allot(60) > secs(1_000_000));
+    assert!(byo_yomi.record(secs(1)));
+}
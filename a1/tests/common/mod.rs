@@ -0,0 +1,33 @@
+//! Helpers shared by the integration tests.
+
+// Each test crate uses only some of these
+#![allow(dead_code)]
+
+use reversi::{Game, Move};
+
+/// One of the shortest games: after these nine moves Black has every disc.
+pub const SHORTEST_GAME: [&str; 9] = ["d3", "c3", "b3", "d2", "e1", "d6", "d7", "e3", "f4"];
+
+/// Small xorshift generator so random games and positions are reproducible.
+pub fn next(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+/// A random finished game on a `size` board with at least one forced pass.
+pub fn game_with_pass(size: usize, state: &mut u64) -> Game {
+    loop {
+        let mut game = Game::with_size(size).expect("test sizes are valid");
+        while !game.is_over() {
+            let legal = game.board().legal_moves(game.current_player());
+            let m = &legal[next(state) as usize % legal.len()];
+            game.play(m.to_move()).expect("legal moves can be played");
+        }
+        // The final pass of a finished game is not recorded, so any pass is forced
+        if game.history().iter().any(|ply| ply.mv == Move::Pass) {
+            return game;
+        }
+    }
+}
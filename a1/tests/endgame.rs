@@ -0,0 +1,143 @@
+mod common;
+
+use reversi::endgame::{within_reach, Solver, SOLVE_EMPTIES};
+use reversi::{Board, Cell, Move};
+
+use common::next;
+
+// Final disc difference for `color` under perfect play, by plain minimax.
+fn minimax(board: &Board, color: Cell, passed: bool) -> i32 {
+    let legal = board.legal_moves(color);
+    if legal.is_empty() {
+        if passed || board.count_empty() == 0 {
+            let (black, white) = board.count_pieces();
+            let difference = black as i32 - white as i32;
+            return if color == Cell::Black { difference } else { -difference };
+        }
+        return -minimax(board, color.opposite(), true);
+    }
+    legal
+        .iter()
+        .map(|m| {
+            let mut next = *board;
+            next.apply_move(m.row, m.col, color);
+            -minimax(&next, color.opposite(), false)
+        })
+        .max()
+        .expect("there is at least one move")
+}
+
+// Play random moves on a board of `size` until `empties` squares are left,
+// returning the position and the side to move, or `None` if the game ended
+// first.
+fn random_position(size: usize, empties: usize, state: &mut u64) -> Option<(Board, Cell)> {
+    let mut board = Board::with_size(size).expect("test sizes are valid");
+    let mut color = Cell::Black;
+    while board.count_empty() > empties {
+        let legal = board.legal_moves(color);
+        if legal.is_empty() {
+            if !board.has_valid_moves(color.opposite()) {
+                return None;
+            }
+        } else {
+            let m = &legal[next(state) as usize % legal.len()];
+            board.apply_move(m.row, m.col, color);
+        }
+        color = color.opposite();
+    }
+    Some((board, color))
+}
+
+// Check every solver entry point on `count` random positions, half of them
+// with the side to move forced to pass.
+fn check_random_positions(size: usize, count: usize, seed: u64) {
+    let mut state = seed;
+    let mut solver = Solver::new();
+    let (mut moving, mut passing) = (0, 0);
+    while moving + passing < count {
+        let empties = 6 + next(&mut state) as usize % 5;
+        let Some((board, mut color)) = random_position(size, empties, &mut state) else {
+            continue;
+        };
+        // A side with no moves while the other has some makes a pass position
+        if !board.has_valid_moves(color.opposite()) {
+            color = color.opposite();
+        }
+        let legal = board.legal_moves(color);
+        if legal.is_empty() {
+            if passing == count / 2 {
+                continue;
+            }
+            passing += 1;
+        } else {
+            if moving == count - count / 2 {
+                continue;
+            }
+            moving += 1;
+        }
+
+        // Value of each legal move by minimax, best first
+        let mut values: Vec<(Move, i32)> = legal
+            .iter()
+            .map(|m| {
+                let mut next = board;
+                next.apply_move(m.row, m.col, color);
+                (m.to_move(), -minimax(&next, color.opposite(), false))
+            })
+            .collect();
+        values.sort_by_key(|&(_, value)| -value);
+        let expected = match values.first() {
+            Some(&(_, value)) => value,
+            None => minimax(&board, color, false),
+        };
+
+        let exact = solver.solve_exact(&board, color);
+        assert_eq!(exact.score, expected, "exact score for {:?} on\n{}", color, board.grid_text());
+        let wld = solver.solve_wld(&board, color);
+        assert_eq!(wld.score, expected.signum(), "win/loss/draw for {:?} on\n{}", color, board.grid_text());
+        if legal.is_empty() {
+            assert_eq!(exact.best_move, Move::Pass);
+            assert_eq!(wld.best_move, Move::Pass);
+        } else {
+            // The chosen moves must reach the scores claimed for them
+            let value = |mv: Move| values.iter().find(|&&(m, _)| m == mv).map(|&(_, value)| value);
+            assert_eq!(value(exact.best_move), Some(expected));
+            assert_eq!(value(wld.best_move).map(i32::signum), Some(expected.signum()));
+        }
+
+        // Ties may come in any order, but the scores must line up
+        let ranked = solver.rank_moves(&board, color);
+        let scores: Vec<i32> = ranked.iter().map(|result| result.score).collect();
+        assert_eq!(scores, values.iter().map(|&(_, value)| value).collect::<Vec<_>>());
+        for result in &ranked {
+            let pair = (result.best_move, result.score);
+            assert!(values.contains(&pair), "{:?} ranked at {} on\n{}", pair.0, pair.1, board.grid_text());
+        }
+    }
+}
+
+#[test]
+fn bitboard_solver_matches_minimax() {
+    check_random_positions(8, 30, 0x9e37_79b9_7f4a_7c15);
+}
+
+#[test]
+fn board_solver_matches_minimax() {
+    check_random_positions(6, 30, 0x2545_f491_4f6c_dd1d);
+}
+
+#[test]
+fn only_small_endgames_are_within_reach() {
+    assert!(!within_reach(&Board::new()));
+    let mut state = 0x9e37_79b9_7f4a_7c15;
+    let (board, _) = random_position(8, SOLVE_EMPTIES, &mut state).expect("the game lasts that long");
+    assert!(within_reach(&board));
+    let mut larger = Board::empty_with_size(10).expect("10x10 is supported");
+    for row in 0..10 {
+        for col in 0..10 {
+            larger.set(row, col, if row + col < 18 { Cell::Black } else { Cell::Empty });
+        }
+    }
+    assert!(larger.count_empty() <= SOLVE_EMPTIES);
+    assert!(!within_reach(&larger));
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use reversi::notation::parse_square;
+use reversi::{Cell, Event, Game, Move, Outcome, Ply, Position};
+
+use common::{game_with_pass, SHORTEST_GAME};
+
+// The position and history after each placement of `game`, replayed from
+// its start, beginning with the start itself.
+fn snapshots(game: &Game) -> Vec<(Position, Vec<Ply>)> {
+    let mut replay = Game::from_position(game.start());
+    let mut states = vec![(replay.position(), replay.history().to_vec())];
+    for ply in game.history().iter().filter(|ply| ply.mv != Move::Pass) {
+        replay.play(ply.mv).expect("the game's moves are legal");
+        states.push((replay.position(), replay.history().to_vec()));
+    }
+    states
+}
+
+#[test]
+fn undo_and_redo_step_over_forced_passes() {
+    let mut state = 0x9e37_79b9_7f4a_7c15;
+    let mut game = game_with_pass(8, &mut state);
+    let states = snapshots(&game);
+    assert_eq!(states.last().map(|(_, history)| history.as_slice()), Some(game.history()));
+
+    for (position, history) in states.iter().rev().skip(1) {
+        assert!(game.undo());
+        assert_eq!(&game.position(), position);
+        assert_eq!(game.history(), history.as_slice());
+    }
+    assert!(!game.undo());
+
+    for (position, history) in states.iter().skip(1) {
+        assert!(game.redo().is_some());
+        assert_eq!(&game.position(), position);
+        assert_eq!(game.history(), history.as_slice());
+    }
+    assert!(game.redo().is_none());
+    assert!(game.is_over());
+}
+
+#[test]
+fn a_new_move_clears_redo() {
+    let mut game = Game::new();
+    for mv in ["f5", "d6", "c3"] {
+        let (row, col) = parse_square(mv).expect("test squares are valid");
+        game.play(Move::Place { row, col }).expect("the opening is legal");
+    }
+    assert!(game.undo());
+    assert!(game.undo());
+    let legal = game.board().legal_moves(game.current_player());
+    let other = legal.iter().map(|m| m.to_move()).find(|&mv| mv != Move::Place { row: 5, col: 3 }).expect("White has another reply");
+    game.play(other).expect("legal moves can be played");
+    assert!(game.redo().is_none());
+    assert_eq!(game.history().len(), 2);
+    assert!(game.undo());
+    assert_eq!(game.redo().map(|events| events.is_empty()), Some(true));
+    assert_eq!(game.history().last().map(|ply| ply.mv), Some(other));
+}
+
+fn position(text: &str) -> Position {
+    text.parse().expect("test positions are valid")
+}
+
+#[test]
+fn blocked_side_passes_by_itself() {
+    // Black cannot move from the start, so the game passes for Black
+    let mut game = Game::from_position(position(&format!("OX{} X", "-".repeat(62))));
+    assert_eq!(game.current_player(), Cell::White);
+    assert_eq!(game.history(), [Ply { player: Cell::Black, mv: Move::Pass, flips: Vec::new() }]);
+    assert!(!game.is_over());
+    assert!(!game.is_legal(Move::Pass));
+    assert_eq!(game.play(Move::Place { row: 0, col: 2 }), Some(vec![Event::GameOver(Outcome::Win { winner: Cell::White, margin: 3 })]));
+
+    // Every pass a random game forces is reported and leaves the other side to move
+    let mut state = 0x2545_f491_4f6c_dd1d;
+    let finished = game_with_pass(8, &mut state);
+    let mut replay = Game::new();
+    let mut passes = 0;
+    for ply in finished.history().iter().filter(|ply| ply.mv != Move::Pass) {
+        let events = replay.play(ply.mv).expect("the game's moves are legal");
+        if let Some(&Event::Passed(player)) = events.first() {
+            passes += 1;
+            assert!(!replay.board().has_valid_moves(player));
+            assert_eq!(replay.current_player(), player.opposite());
+            assert_eq!(replay.history().last().map(|ply| (ply.player, ply.mv)), Some((player, Move::Pass)));
+        }
+    }
+    assert!(passes > 0);
+}
+
+#[test]
+fn game_ends_when_both_sides_are_blocked() {
+    // Neither lone disc can capture anything, with squares still empty
+    let game = Game::from_position(position(&format!("X{}O X", "-".repeat(62))));
+    assert!(game.is_over());
+    assert!(game.history().is_empty());
+    assert_eq!(game.outcome(), Some(Outcome::Draw));
+    assert!(!game.is_legal(Move::Pass));
+
+    let mut game = Game::new();
+    let mut events = Vec::new();
+    for mv in SHORTEST_GAME {
+        assert_eq!(game.outcome(), None);
+        let (row, col) = parse_square(mv).expect("test squares are valid");
+        events = game.play(Move::Place { row, col }).expect("the game is legal");
+    }
+    let outcome = Outcome::Win { winner: Cell::Black, margin: 13 };
+    assert_eq!(events, vec![Event::GameOver(outcome)]);
+    assert_eq!(game.outcome(), Some(outcome));
+    assert_eq!(outcome.to_string(), "Black wins by 13 points!");
+    assert!(game.play(Move::Pass).is_none());
+}
+
+#[test]
+fn undo_turn_takes_back_the_computer_replies() {
+    let mut game = Game::new();
+    for mv in ["f5", "d6", "c3", "d3"] {
+        let (row, col) = parse_square(mv).expect("test squares are valid");
+        game.play(Move::Place { row, col }).expect("the opening is legal");
+    }
+    // White is the computer: Black's c3 goes too, back to Black's turn
+    assert!(game.undo_turn(|color| color == Cell::White));
+    assert_eq!((game.history().len(), game.current_player()), (2, Cell::Black));
+    // With no computer it is one move at a time
+    assert!(game.undo_turn(|_| false));
+    assert_eq!((game.history().len(), game.current_player()), (1, Cell::White));
+    // A computer playing both sides takes everything back
+    assert!(game.undo_turn(|_| true));
+    assert!(game.history().is_empty());
+    assert!(!game.undo_turn(|_| true));
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use reversi::ggf::{parse_records, GgfError, Player, Record};
+use reversi::Game;
+
+use common::game_with_pass;
+
+fn error(text: &str) -> GgfError {
+    text.parse::<Record>().expect_err(text)
+}
+
+#[test]
+fn round_trips_a_game_with_a_pass() {
+    let mut state = 0x9e37_79b9_7f4a_7c15;
+    let game = game_with_pass(6, &mut state);
+    let mut record = Record::from_game(&game);
+    record.black = Player { name: "alpha".to_string(), rating: Some(2034.85) };
+    record.moves[0].eval = Some(-1.5);
+    record.moves[0].time = Some(2.25);
+
+    let text = record.to_string();
+    assert!(text.contains("BO[6 ------ ------ --O*-- --*O-- ------ ------ *]"), "{}", text);
+    assert!(text.contains("[pa]"), "{}", text);
+    let read: Record = text.parse().expect("written records can be read");
+    assert_eq!(read, record);
+
+    let replayed = read.to_game().expect("the recorded game is legal");
+    assert_eq!(replayed.history(), game.history());
+    assert_eq!(replayed.position(), game.position());
+    assert_eq!(read.result, record.result);
+}
+
+#[test]
+fn reads_several_records() {
+    let one = Record::from_game(&Game::new()).to_string();
+    let records = parse_records(&format!("{}\n{}\n", one, one)).expect("both records are valid");
+    assert_eq!(records.len(), 2);
+    assert_eq!(parse_records(""), Ok(Vec::new()));
+}
+
+#[test]
+fn malformed_records_are_errors() {
+    let board = "BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *]";
+    assert_eq!(error("GM[Othello]"), GgfError::NoRecord);
+    assert_eq!(error("(;GM[Othello]BO[8 ---"), GgfError::Unterminated);
+    assert_eq!(error("(;GM[Othello]B[f5]"), GgfError::Unterminated);
+    assert_eq!(error("(;GM[Othello]B[f5];)"), GgfError::MissingBoard);
+    assert_eq!(error("(;GM[Chess];)"), GgfError::BadValue { tag: "GM".to_string(), value: "Chess".to_string() });
+    assert_eq!(
+        error(&format!("(;GM[Othello]RB[strong]{};)", board)),
+        GgfError::BadValue { tag: "RB".to_string(), value: "strong".to_string() }
+    );
+    // The size must match the squares given
+    assert_eq!(
+        error("(;GM[Othello]BO[6 ---- ---- -O*- -*O- *];)"),
+        GgfError::BadValue { tag: "BO".to_string(), value: "6 ---- ---- -O*- -*O- *".to_string() }
+    );
+    assert_eq!(
+        error(&format!("(;GM[Othello]{}B[f5]W[z9];)", board)),
+        GgfError::BadMove { index: 2, token: "z9".to_string() }
+    );
+    assert_eq!(
+        error(&format!("(;GM[Othello]{}B[f5/abc];)", board)),
+        GgfError::BadMove { index: 1, token: "f5/abc".to_string() }
+    );
+}
+
+#[test]
+fn illegal_moves_are_errors() {
+    let board = "BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *]";
+    let replay = |moves: &str| format!("(;GM[Othello]{}{};)", board, moves).parse::<Record>().expect("the record reads").to_game();
+    assert!(replay("B[f5]W[d6]").is_ok());
+    assert_eq!(replay("B[f5]W[a1]").unwrap_err(), GgfError::IllegalMove { index: 2, token: "a1".to_string() });
+    // Out of turn, and a pass that was not forced
+    assert_eq!(replay("B[f5]B[d6]").unwrap_err(), GgfError::IllegalMove { index: 2, token: "d6".to_string() });
+    assert_eq!(replay("B[f5]W[pa]").unwrap_err(), GgfError::IllegalMove { index: 2, token: "pa".to_string() });
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use reversi::gtp;
+use reversi::search::AlphaBeta;
+
+use common::SHORTEST_GAME;
+
+fn session(commands: &str) -> String {
+    let mut output = Vec::new();
+    gtp::run(commands.as_bytes(), &mut output, &mut AlphaBeta::new(1), 8).expect("writing to a Vec cannot fail");
+    String::from_utf8(output).expect("replies are UTF-8")
+}
+
+#[test]
+fn genmove_out_of_turn_is_an_error() {
+    assert_eq!(session("1 genmove w\n"), "?1 not this color's turn\n\n");
+    // The refused request left the game alone, so Black can still move
+    let replies = session("genmove w\ngenmove b\n");
+    assert!(replies.ends_with("= d3\n\n"), "{}", replies);
+}
+
+#[test]
+fn genmove_passes_once_the_game_is_over() {
+    let mut commands: String = SHORTEST_GAME.iter().zip(["b", "w"].iter().cycle()).map(|(mv, color)| format!("play {} {}\n", color, mv)).collect();
+    commands.push_str("genmove w\ngenmove b\n");
+    let replies = session(&commands);
+    assert!(!replies.contains('?'), "{}", replies);
+    assert!(replies.ends_with("= pass\n\n= pass\n\n"), "{}", replies);
+}
+
+#[test]
+fn showboard_uses_vertex_coordinates() {
+    let replies = session("play b f5\nshowboard\n");
+    let lines: Vec<&str> = replies.lines().collect();
+    assert_eq!(lines[3], "   a b c d e f g h");
+    assert_eq!(lines[7], " 4 . . . W B . . .");
+    assert_eq!(lines[8], " 5 . . . B B B . .");
+}
+
+#[test]
+fn final_score_follows_the_outcome() {
+    assert_eq!(session("final_score\n"), "= 0\n\n");
+    assert_eq!(session("play b f5\nfinal_score\n"), "= \n\n= B+3\n\n");
+    let mut commands: String = SHORTEST_GAME.iter().zip(["b", "w"].iter().cycle()).map(|(mv, color)| format!("play {} {}\n", color, mv)).collect();
+    commands.push_str("final_score\n");
+    assert!(session(&commands).ends_with("= B+13\n\n"));
+}
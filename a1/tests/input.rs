@@ -0,0 +1,50 @@
+use reversi::input::{parse_input, Command, InputError};
+use reversi::{Game, Move};
+
+fn parse(text: &str) -> Result<Command, InputError> {
+    parse_input(text, &Game::new())
+}
+
+#[test]
+fn squares_in_either_notation() {
+    let d3 = Ok(Command::Play(Move::Place { row: 2, col: 3 }));
+    assert_eq!(parse("d3"), d3);
+    assert_eq!(parse(" D3 "), d3);
+    assert_eq!(parse("cd"), d3);
+    assert_eq!(parse("CD"), d3);
+}
+
+#[test]
+fn commands() {
+    assert_eq!(parse("UNDO"), Ok(Command::Undo));
+    assert_eq!(parse("hint"), Ok(Command::Hint(1)));
+    assert_eq!(parse("hint 3"), Ok(Command::Hint(3)));
+    assert_eq!(parse("save My Game.txt"), Ok(Command::Save("My Game.txt".to_string())));
+    assert_eq!(parse("exit"), Ok(Command::Quit));
+}
+
+#[test]
+fn each_error() {
+    assert_eq!(parse("   "), Err(InputError::Empty));
+    assert_eq!(parse("castle"), Err(InputError::UnknownCommand("castle".to_string())));
+    assert_eq!(parse("hint 0"), Err(InputError::UnknownCommand("hint 0".to_string())));
+    assert_eq!(parse("load"), Err(InputError::MissingFile("load".to_string())));
+    assert_eq!(parse("i9"), Err(InputError::OutOfRange("i9".to_string())));
+    assert_eq!(parse("dz"), Err(InputError::OutOfRange("dz".to_string())));
+    assert_eq!(parse("a0"), Err(InputError::OutOfRange("a0".to_string())));
+    assert_eq!(parse("d4"), Err(InputError::Occupied { row: 3, col: 3 }));
+    assert_eq!(parse("a1"), Err(InputError::NoCapture { row: 0, col: 0 }));
+    assert_eq!(parse("a1").unwrap_err().to_string(), "a1 does not capture any discs.");
+}
+
+#[test]
+fn never_panics() {
+    assert_eq!(parse(""), Err(InputError::Empty));
+    for text in ["é", "é5", "dé", "ée", "d٣", "日本", "a\u{0}", "💥", "hint 99999999999999999999999"] {
+        assert_eq!(parse(text), Err(InputError::UnknownCommand(text.to_string())), "{:?}", text);
+    }
+    // Rows too large for any integer are still just off the board
+    for text in ["a99999999999999999999999", "p17", "q1"] {
+        assert_eq!(parse(text), Err(InputError::OutOfRange(text.to_string())), "{:?}", text);
+    }
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use reversi::endgame::Solver;
+use reversi::mcts::{Budget, Mcts, Playout};
+use reversi::notation::{parse_square, parse_transcript};
+use reversi::{Cell, Game, Move, Position};
+
+use common::{next, SHORTEST_GAME};
+
+fn place(name: &str) -> Move {
+    let (row, col) = parse_square(name).expect("test squares are valid");
+    Move::Place { row, col }
+}
+
+#[test]
+fn same_seed_same_move() {
+    let game = parse_transcript("c4c3c2d6f6f5d7c7b7d3e2d8g6b3").expect("the transcript is legal");
+    for playout in [Playout::Random, Playout::Heavy] {
+        for seed in [1, 2, 3] {
+            let search = || Mcts::new(Budget::Iterations(300), playout, seed).search(game.board(), game.current_player());
+            let (first, second) = (search(), search());
+            assert_eq!(first, second, "{:?} with seed {}", playout, seed);
+            assert_eq!(first.iterations, 300);
+            assert!(game.is_legal(first.best_move));
+        }
+    }
+}
+
+#[test]
+fn finds_a_forced_win() {
+    // Black's f4 ends the shortest game with every disc; no other move wins
+    let text: String = SHORTEST_GAME[..8].concat();
+    let game = parse_transcript(&text).expect("the transcript is legal");
+    assert!(game.board().legal_moves(Cell::Black).len() > 1);
+    for playout in [Playout::Random, Playout::Heavy] {
+        let result = Mcts::new(Budget::Iterations(200), playout, 7).search(game.board(), Cell::Black);
+        assert_eq!(result.best_move, place("f4"), "{:?}", playout);
+        assert_eq!(result.win_rate, 1.0);
+    }
+}
+
+#[test]
+fn heavy_playouts_find_winning_corners() {
+    // Endgames where taking a corner is the only winning move, which heavy
+    // playouts, preferring corners, find with a small budget
+    let mut state = 0x9e37_79b9_7f4a_7c15;
+    let mut found = 0;
+    while found < 3 {
+        let mut game = Game::new();
+        while game.board().count_empty() > 8 && !game.is_over() {
+            let legal = game.board().legal_moves(game.current_player());
+            game.play(legal[next(&mut state) as usize % legal.len()].to_move()).expect("legal moves can be played");
+        }
+        if game.is_over() {
+            continue;
+        }
+        let (board, color) = (game.board(), game.current_player());
+        let ranked = Solver::new().rank_moves(board, color);
+        let winning: Vec<Move> = ranked.iter().filter(|result| result.score > 0).map(|result| result.best_move).collect();
+        let corners = [place("a1"), place("h1"), place("a8"), place("h8")];
+        if ranked.len() < 3 || winning.len() != 1 || !corners.contains(&winning[0]) {
+            continue;
+        }
+        found += 1;
+        let result = Mcts::new(Budget::Iterations(500), Playout::Heavy, 1).search(board, color);
+        assert_eq!(result.best_move, winning[0], "on\n{}", board.grid_text());
+    }
+}
+
+#[test]
+fn forced_moves_need_no_search() {
+    // Black has no move and passes; White's only move is played at once
+    let blocked: Position = format!("OX{} X", "-".repeat(62)).parse().expect("the position is valid");
+    let pass = Mcts::new(Budget::Iterations(100), Playout::Heavy, 1).search(&blocked.board, Cell::Black);
+    assert_eq!((pass.best_move, pass.iterations), (Move::Pass, 0));
+    let only = Mcts::new(Budget::Iterations(100), Playout::Heavy, 1).search(&blocked.board, Cell::White);
+    assert_eq!((only.best_move, only.iterations), (place("c1"), 0));
+}
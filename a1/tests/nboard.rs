@@ -0,0 +1,35 @@
+mod common;
+
+use reversi::nboard;
+
+use common::SHORTEST_GAME;
+
+fn session(commands: &str) -> Vec<String> {
+    let mut output = Vec::new();
+    nboard::run(commands.as_bytes(), &mut output, 4).expect("writing to a Vec cannot fail");
+    String::from_utf8(output).expect("replies are UTF-8").lines().map(str::to_string).collect()
+}
+
+#[test]
+fn heuristic_evaluations_are_scaled_to_discs() {
+    let replies = session("nboard 2\nmove F5\nmove D6\nmove C3\ngo\n");
+    let reply = replies.iter().find(|line| line.starts_with("=== ")).expect("go is answered");
+    let eval: f64 = reply.split('/').nth(1).and_then(|eval| eval.parse().ok()).expect("the reply has an evaluation");
+    // The opening is close to even; raw heuristic points would be far larger
+    assert!(eval.abs() < 10.0, "{}", reply);
+}
+
+#[test]
+fn proven_wins_are_exact_disc_differences() {
+    // The shortest game, stopped one move before its end, where Black wins
+    // with every disc on the board
+    let moves: String = SHORTEST_GAME[..8].iter().map(|mv| format!("move {}\n", mv.to_ascii_uppercase())).collect();
+    let replies = session(&format!("nboard 2\n{}go\n", moves));
+    assert!(replies.iter().any(|line| line.starts_with("=== F4/13.00/")), "{:?}", replies);
+}
+
+#[test]
+fn pass_is_only_accepted_after_a_forced_one() {
+    let replies = session("nboard 2\nmove PA\n");
+    assert_eq!(replies.last().map(String::as_str), Some("status illegal pass"));
+}
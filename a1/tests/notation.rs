@@ -0,0 +1,44 @@
+mod common;
+
+use reversi::notation::{parse_transcript, play_transcript, write_transcript, TranscriptError};
+use reversi::{Game, Position};
+
+use common::game_with_pass;
+
+#[test]
+fn transcripts_round_trip_with_passes() {
+    let mut state = 0x9e37_79b9_7f4a_7c15;
+    let game = game_with_pass(8, &mut state);
+    let text = write_transcript(game.history());
+    assert!(text.contains("pa"), "{}", text);
+    let replayed = parse_transcript(&text).expect("written transcripts can be read");
+    assert_eq!(replayed.history(), game.history());
+    assert_eq!(write_transcript(replayed.history()), text);
+
+    // The passes may also be left out, or written another way
+    let implicit = parse_transcript(&text.replace("pa", "")).expect("passes can be left implicit");
+    assert_eq!(implicit.history(), game.history());
+    let dashes = parse_transcript(&text.replace("pa", " -- ")).expect("passes can be written as --");
+    assert_eq!(dashes.history(), game.history());
+}
+
+#[test]
+fn unforced_passes_are_illegal() {
+    assert_eq!(parse_transcript("f5pa").unwrap_err(), TranscriptError::IllegalMove { index: 2, token: "pa".to_string() });
+    let mut state = 0x2545_f491_4f6c_dd1d;
+    let text = write_transcript(game_with_pass(8, &mut state).history());
+    // A pass only answers the one forced pass before it
+    let doubled = text.replacen("pa", "papa", 1);
+    let index = text[..text.find("pa").unwrap()].len() / 2 + 2;
+    assert_eq!(parse_transcript(&doubled).unwrap_err(), TranscriptError::IllegalMove { index, token: "pa".to_string() });
+}
+
+#[test]
+fn a_game_that_starts_with_a_pass() {
+    // Black cannot move, so the game passes for Black before any move is read
+    let position: Position = format!("OX{} X", "-".repeat(62)).parse().expect("the position is valid");
+    let mut game = Game::from_position(position);
+    play_transcript(&mut game, "pa c1").expect("the forced pass can be written out");
+    assert!(game.is_over());
+    assert_eq!(write_transcript(game.history()), "pac1");
+}
@@ -0,0 +1,47 @@
+use reversi::perft::{divide, perft};
+use reversi::{Board, Cell, Move, Position};
+
+// Published perft counts from the initial position, passes counted as plies.
+const INITIAL_COUNTS: [u64; 8] = [4, 12, 56, 244, 1396, 8200, 55092, 390216];
+
+fn position(text: &str) -> Position {
+    text.parse().expect("test positions are valid")
+}
+
+#[test]
+fn initial_position_matches_reference_counts() {
+    let board = Board::new();
+    assert_eq!(perft(&board, Cell::Black, 0), 1);
+    for (depth, &expected) in (1..).zip(INITIAL_COUNTS.iter()) {
+        assert_eq!(perft(&board, Cell::Black, depth), expected, "perft {}", depth);
+    }
+}
+
+#[test]
+fn divide_adds_up_to_perft() {
+    let board = Board::new();
+    let counts = divide(&board, Cell::Black, 6);
+    let moves: Vec<Move> = counts.iter().map(|&(mv, _)| mv).collect();
+    let expected: Vec<Move> = board.legal_moves(Cell::Black).iter().map(|m| m.to_move()).collect();
+    assert_eq!(moves, expected);
+    // The four openings are symmetric
+    assert!(counts.iter().all(|&(_, nodes)| nodes == INITIAL_COUNTS[5] / 4));
+    assert_eq!(counts.iter().map(|&(_, nodes)| nodes).sum::<u64>(), INITIAL_COUNTS[5]);
+}
+
+#[test]
+fn pass_takes_a_ply() {
+    // Black cannot move; White can only take b1 with c1, which ends the game
+    let start = position(&format!("OX{} X", "-".repeat(62)));
+    assert_eq!(perft(&start.board, Cell::Black, 1), 1);
+    assert_eq!(perft(&start.board, Cell::Black, 2), 1);
+    assert_eq!(perft(&start.board, Cell::Black, 5), 1);
+    assert_eq!(divide(&start.board, Cell::Black, 2), vec![(Move::Pass, 1)]);
+}
+
+#[test]
+fn finished_game_is_one_leaf() {
+    let end = position(&format!("O{} X", "-".repeat(63)));
+    assert_eq!(perft(&end.board, Cell::Black, 3), 1);
+    assert!(divide(&end.board, Cell::Black, 3).is_empty());
+}
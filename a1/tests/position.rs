@@ -0,0 +1,73 @@
+mod common;
+
+use reversi::position::ParsePositionError;
+use reversi::{Board, Cell, Position};
+
+use common::next;
+
+fn random_board(size: usize, state: &mut u64) -> Board {
+    let mut board = Board::empty_with_size(size).expect("test sizes are valid");
+    for row in 0..size {
+        for col in 0..size {
+            board.set(row, col, [Cell::Empty, Cell::Black, Cell::White][next(state) as usize % 3]);
+        }
+    }
+    board
+}
+
+#[test]
+fn positions_round_trip() {
+    let mut state = 0x9e37_79b9_7f4a_7c15;
+    for size in [4, 8, 16] {
+        for to_move in [Cell::Black, Cell::White] {
+            let position = Position { board: random_board(size, &mut state), to_move };
+            let text = position.to_string();
+            assert_eq!(text.len(), size * size + 2);
+            assert!(text.ends_with(if to_move == Cell::Black { " X" } else { " O" }), "{}", text);
+            assert_eq!(text.parse(), Ok(position));
+            assert_eq!(position.board.to_string().parse(), Ok(position.board));
+        }
+    }
+}
+
+#[test]
+fn initial_position_text() {
+    let text = "---------------------------OX------XO--------------------------- X";
+    let position: Position = text.parse().expect("the initial position is valid");
+    assert_eq!(position, Position { board: Board::new(), to_move: Cell::Black });
+    assert_eq!(position.to_string(), text);
+    // OBF move scores after a `;` are ignored
+    assert_eq!(format!("{};c4:+0;", text).parse(), Ok(position));
+}
+
+#[test]
+fn bad_length_reports_the_count() {
+    let error = format!("{} X", "-".repeat(63)).parse::<Position>().unwrap_err();
+    assert_eq!(error, ParsePositionError::BadLength { found: 63 });
+    assert!(error.to_string().ends_with("found 63"), "{}", error);
+}
+
+#[test]
+fn bad_cell_reports_the_square() {
+    // Index 10 is c2 on an 8x8 board
+    let text = format!("{}x{} X", "-".repeat(10), "-".repeat(53));
+    let error = text.parse::<Position>().unwrap_err();
+    assert_eq!(error, ParsePositionError::BadCell { index: 10, size: 8, found: 'x' });
+    assert_eq!(error.to_string(), "square c2 is 'x', expected 'X', 'O' or '-'");
+}
+
+#[test]
+fn bad_side_reports_the_text() {
+    let error = format!("{} B", "-".repeat(64)).parse::<Position>().unwrap_err();
+    assert_eq!(error, ParsePositionError::BadSide { found: "B".to_string() });
+    assert_eq!(error.to_string(), "side to move is 'B', expected 'X' or 'O'");
+    let missing = "-".repeat(64).parse::<Position>().unwrap_err();
+    assert_eq!(missing, ParsePositionError::MissingSide);
+}
+
+#[test]
+fn trailing_text_reports_the_text() {
+    let error = format!("{} O extra", "-".repeat(16)).parse::<Position>().unwrap_err();
+    assert_eq!(error, ParsePositionError::TrailingText { found: "extra".to_string() });
+    assert_eq!(error.to_string(), "unexpected 'extra' after the side to move");
+}
@@ -0,0 +1,81 @@
+use reversi::render::{grid, Theme, RESET, THEMES};
+use reversi::{Board, Cell};
+
+// Strip the escape sequences from `text`, which all end in 'm'.
+fn plain(text: &str) -> String {
+    let mut plain = String::new();
+    let mut in_escape = false;
+    for c in text.chars() {
+        if c == '\x1b' {
+            in_escape = true;
+        } else if in_escape {
+            in_escape = c != 'm';
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+#[test]
+fn grids_are_labelled_like_squares() {
+    let theme = &THEMES[0];
+    for size in [6, 8, 10] {
+        let board = Board::with_size(size).expect("test sizes are valid");
+        let text = grid(&board, Some(Cell::Black), theme);
+        let lines: Vec<String> = text.lines().map(plain).collect();
+        assert_eq!(lines.len(), size + 1);
+        let labels: String = "abcdefghij".chars().take(size).map(|c| format!(" {}", c)).collect();
+        assert_eq!(lines[0], format!("   {}", labels));
+
+        // The centre four discs, and Black's four openings dotted
+        let center = size / 2;
+        for (row, line) in lines[1..].iter().enumerate() {
+            assert!(line.starts_with(&format!("{:>2} ", row + 1)), "{:?}", line);
+            let cells: Vec<char> = line[3..].chars().skip(1).step_by(2).collect();
+            assert_eq!(cells.len(), size, "{:?}", line);
+            for (col, &glyph) in cells.iter().enumerate() {
+                let expected = match board.get(row, col) {
+                    Cell::Black => '●',
+                    Cell::White => '○',
+                    Cell::Empty if board.is_valid_move(row, col, Cell::Black) => '·',
+                    Cell::Empty => ' ',
+                };
+                assert_eq!(glyph, expected, "{} at row {} col {} of {}x{}", glyph, row, col, size, size);
+            }
+            if row == center - 1 {
+                assert_eq!(cells[center - 1], '○');
+                assert_eq!(cells[center], '●');
+            }
+        }
+        assert_eq!(text.matches('·').count(), 4);
+        // Every row turns the colours off again
+        assert!(text.lines().skip(1).all(|line| line.ends_with(RESET)));
+    }
+}
+
+#[test]
+fn finished_games_have_no_dots() {
+    let board = Board::with_size(10).expect("10x10 is supported");
+    for theme in &THEMES {
+        assert!(!grid(&board, None, theme).contains('·'), "{}", theme.name);
+    }
+}
+
+#[test]
+fn themes_by_name() {
+    assert_eq!(Theme::by_name("DARK").map(|theme| theme.name), Some("dark"));
+    assert!(Theme::by_name("neon").is_none());
+    let square = THEMES[1].square(Cell::White, false);
+    assert_eq!(plain(&square), "○");
+    assert_eq!(THEMES[1].square(Cell::Empty, false), " ");
+}
+
+#[test]
+fn plain_grids_use_the_same_labels() {
+    let text = Board::with_size(10).expect("10x10 is supported").grid_text();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], "   abcdefghij");
+    assert_eq!(lines[5], " 5 ....WB....");
+    assert_eq!(lines[10], "10 ..........");
+}
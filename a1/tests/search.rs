@@ -0,0 +1,238 @@
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::{Duration, Instant};
+
+mod common;
+
+use reversi::notation::{parse_square, parse_transcript};
+use reversi::search::{evaluate, final_score, AlphaBeta, Iteration, WIN_SCORE};
+use reversi::{Board, Cell, Game, Move, Position};
+
+use common::next;
+
+// A middle-game position 14 plies in, which no search from the initial
+// position can reach within 14 plies.
+fn middle_game() -> Game {
+    parse_transcript("c4c3c2d6f6f5d7c7b7d3e2d8g6b3").expect("the transcript is legal")
+}
+
+// Deepen `search` from the initial position and set its stop flag from
+// another thread after `after`, returning how long the search took.
+fn stopped_deepen(search: &mut AlphaBeta, after: Duration) -> Duration {
+    let stop = search.stop_flag();
+    let start = Instant::now();
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            thread::sleep(after);
+            stop.store(true, Ordering::Relaxed);
+        });
+        let result = search.deepen(&Board::new(), Cell::Black, None, &mut |_| {});
+        assert_ne!(result.best_move, Move::Pass);
+    });
+    start.elapsed()
+}
+
+#[test]
+fn stop_flag_aborts_deepen() {
+    let mut search = AlphaBeta::new(60);
+    let took = stopped_deepen(&mut search, Duration::from_millis(50));
+    assert!(took < Duration::from_secs(5), "{:?}", took);
+}
+
+#[test]
+fn searches_run_to_the_end_after_an_abort() {
+    let game = middle_game();
+    let (board, color) = (game.board(), game.current_player());
+    let mut fresh = AlphaBeta::new(4);
+    let expected = fresh.search(board, color);
+    let expected_ranks = fresh.rank_moves(board, color);
+
+    let mut search = AlphaBeta::new(60);
+    stopped_deepen(&mut search, Duration::from_millis(50));
+    search.set_depth(4);
+    let result = search.search(board, color);
+    assert_eq!((result.best_move, result.score), (expected.best_move, expected.score));
+    assert!(result.nodes > 1);
+
+    stopped_deepen(&mut search, Duration::from_millis(50));
+    search.set_depth(4);
+    let ranks: Vec<(Move, i32)> = search.rank_moves(board, color).iter().map(|result| (result.best_move, result.score)).collect();
+    assert_eq!(ranks, expected_ranks.iter().map(|result| (result.best_move, result.score)).collect::<Vec<_>>());
+}
+
+#[test]
+fn search_within_keeps_to_its_time() {
+    let mut search = AlphaBeta::new(60);
+    let start = Instant::now();
+    let result = search.search_within(&Board::new(), Cell::Black, Duration::from_millis(200));
+    let took = start.elapsed();
+    assert_ne!(result.best_move, Move::Pass);
+    // Stopping is checked every so many nodes, so allow a little over
+    assert!(took < Duration::from_millis(1000), "{:?}", took);
+}
+
+#[test]
+fn report_gets_every_depth_with_its_line() {
+    let game = middle_game();
+    let mut iterations: Vec<Iteration> = Vec::new();
+    let result = AlphaBeta::new(5).deepen(game.board(), game.current_player(), None, &mut |iteration| iterations.push(iteration.clone()));
+    assert_eq!(iterations.iter().map(|iteration| iteration.depth).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+    assert!(iterations.windows(2).all(|pair| pair[0].nodes <= pair[1].nodes && pair[0].elapsed <= pair[1].elapsed));
+    for iteration in &iterations {
+        assert!(game.is_legal(iteration.pv[0]), "{:?}", iteration);
+    }
+    let last = iterations.last().expect("there is a last depth");
+    assert_eq!(last.pv[0], result.best_move);
+    assert_eq!(last.score, result.score);
+}
+
+#[test]
+fn search_within_accepts_any_time() {
+    let result = AlphaBeta::new(3).search_within(&Board::new(), Cell::Black, Duration::MAX);
+    assert_ne!(result.best_move, Move::Pass);
+}
+
+// Final score or heuristic value of `board` for `color` at `depth`, by plain
+// negamax with the same pass rules as the search: a pass does not use up a
+// ply, and two in a row end the game.
+fn minimax(board: &Board, color: Cell, depth: u32, passed: bool) -> i32 {
+    let legal = board.legal_moves(color);
+    if legal.is_empty() {
+        if passed {
+            return final_score(board, color);
+        }
+        return -minimax(board, color.opposite(), depth, true);
+    }
+    if depth == 0 {
+        return evaluate(board, color);
+    }
+    legal
+        .iter()
+        .map(|m| {
+            let mut next = *board;
+            next.apply_move(m.row, m.col, color);
+            -minimax(&next, color.opposite(), depth - 1, false)
+        })
+        .max()
+        .expect("there is at least one move")
+}
+
+// The initial position with the squares named in `black` and `white` added to the
+// initial position.
+fn with_discs(black: &[&str], white: &[&str], to_move: Cell) -> Board {
+    let mut board = Board::new();
+    for (names, cell) in [(black, Cell::Black), (white, Cell::White)] {
+        for name in names {
+            let (row, col) = parse_square(name).expect("test squares are valid");
+            board.set(row, col, cell);
+        }
+    }
+    assert!(board.has_valid_moves(to_move));
+    board
+}
+
+#[test]
+fn takes_an_available_corner() {
+    let board = with_discs(&["c1"], &["b1"], Cell::Black);
+    for depth in 1..=4 {
+        assert_eq!(AlphaBeta::new(depth).search(&board, Cell::Black).best_move, Move::Place { row: 0, col: 0 }, "depth {}", depth);
+    }
+}
+
+#[test]
+fn avoids_giving_up_a_corner() {
+    // b2 takes c3 but lets White take a1 along the diagonal to e5, while
+    // f4 takes f3 and gives nothing away
+    let mut board = Board::empty();
+    for (name, cell) in [("c3", Cell::White), ("d4", Cell::Black), ("e5", Cell::White), ("f2", Cell::Black), ("f3", Cell::White)] {
+        let (row, col) = parse_square(name).expect("test squares are valid");
+        board.set(row, col, cell);
+    }
+    assert!(board.is_valid_move(1, 1, Cell::Black));
+    for depth in 2..=4 {
+        let best = AlphaBeta::new(depth).search(&board, Cell::Black).best_move;
+        assert_ne!(best, Move::Place { row: 1, col: 1 }, "depth {}", depth);
+        assert!(matches!(best, Move::Place { row, col } if board.is_valid_move(row, col, Cell::Black)));
+    }
+}
+
+#[test]
+fn passes_without_a_legal_move() {
+    let blocked: Position = format!("OX{} X", "-".repeat(62)).parse().expect("the position is valid");
+    let result = AlphaBeta::new(3).search(&blocked.board, Cell::Black);
+    assert_eq!(result.best_move, Move::Pass);
+    // White then takes c1 and every disc
+    assert_eq!(result.score, -(WIN_SCORE + 3));
+    assert_eq!(AlphaBeta::new(3).deepen(&blocked.board, Cell::Black, None, &mut |_| {}).best_move, Move::Pass);
+}
+
+#[test]
+fn matches_plain_minimax() {
+    let mut state = 0x9e37_79b9_7f4a_7c15;
+    let mut checked = 0;
+    while checked < 40 {
+        let mut game = Game::new();
+        let plies = 6 + next(&mut state) as usize % 40;
+        while game.history().len() < plies && !game.is_over() {
+            let legal = game.board().legal_moves(game.current_player());
+            game.play(legal[next(&mut state) as usize % legal.len()].to_move()).expect("legal moves can be played");
+        }
+        if game.is_over() {
+            continue;
+        }
+        checked += 1;
+        let (board, color) = (game.board(), game.current_player());
+        for depth in 2..=3 {
+            let result = AlphaBeta::new(depth).search(board, color);
+            assert_eq!(result.score, minimax(board, color, depth, false), "depth {} on\n{}", depth, board.grid_text());
+            let Move::Place { row, col } = result.best_move else {
+                panic!("passed with legal moves on\n{}", board.grid_text());
+            };
+            let mut next = *board;
+            next.apply_move(row, col, color);
+            assert_eq!(-minimax(&next, color.opposite(), depth - 1, false), result.score);
+        }
+    }
+}
+
+#[test]
+fn rank_moves_scores_every_move() {
+    let mut state = 0x2545_f491_4f6c_dd1d;
+    for _ in 0..20 {
+        let mut game = middle_game();
+        for _ in 0..next(&mut state) % 20 {
+            if game.is_over() {
+                break;
+            }
+            let legal = game.board().legal_moves(game.current_player());
+            game.play(legal[next(&mut state) as usize % legal.len()].to_move()).expect("legal moves can be played");
+        }
+        if game.is_over() {
+            continue;
+        }
+        let (board, color) = (game.board(), game.current_player());
+        let mut search = AlphaBeta::new(3);
+        let ranked = search.rank_moves(board, color);
+        assert!(ranked.windows(2).all(|pair| pair[0].score >= pair[1].score));
+
+        let legal = board.legal_moves(color);
+        assert_eq!(ranked.len(), legal.len());
+        for m in &legal {
+            assert_eq!(ranked.iter().filter(|result| result.best_move == m.to_move()).count(), 1);
+        }
+
+        for result in &ranked {
+            let Move::Place { row, col } = result.best_move else {
+                panic!("ranked a pass on\n{}", board.grid_text());
+            };
+            let mut next = *board;
+            next.apply_move(row, col, color);
+            assert_eq!(result.score, -minimax(&next, color.opposite(), 2, false));
+        }
+
+        // The search may break a tie differently, but not disagree on the score
+        let best = AlphaBeta::new(3).search(board, color);
+        assert_eq!(ranked[0].score, best.score);
+        assert!(ranked.iter().any(|result| result.best_move == best.best_move && result.score == best.score));
+    }
+}
@@ -0,0 +1,17 @@
+use reversi::notation::parse_transcript;
+use reversi::search::AlphaBeta;
+use reversi::smp::LazySmp;
+use reversi::{Game, Move};
+
+#[test]
+fn parallel_search_agrees_with_single_thread() {
+    for (text, depth) in [("", 5), ("f5f4g3e6c4g5d6d3e7b5", 5), ("c4c3c2d6f6f5d7c7b7d3e2d8g6b3", 4)] {
+        let game = if text.is_empty() { Game::new() } else { parse_transcript(text).unwrap() };
+        let (board, color) = (game.board(), game.current_player());
+        let single = AlphaBeta::new(depth).search(board, color);
+        let parallel = LazySmp::new(depth, 4).deepen(board, color, None, &mut |_| {});
+        assert_eq!(parallel.score, single.score, "{} at depth {}", text, depth);
+        assert!(matches!(parallel.best_move, Move::Place { .. }));
+        assert!(parallel.nodes > 0);
+    }
+}
@@ -0,0 +1,75 @@
+use reversi::tt::{Bound, TranspositionTable};
+use reversi::Move;
+
+// Two keys that share a slot in a table of 16 entries.
+const KEY: u64 = 0x1234_5678_9abc_def3;
+const OTHER: u64 = 0x0fed_cba9_8765_4323;
+
+fn place(row: usize, col: usize) -> Option<Move> {
+    Some(Move::Place { row, col })
+}
+
+#[test]
+fn entries_survive_packing() {
+    let table = TranspositionTable::with_entries(16);
+    for (score, depth, bound, best) in [
+        (0, 0, Bound::Exact, None),
+        (-1, 1, Bound::Lower, place(0, 0)),
+        (10_064, 60, Bound::Upper, place(15, 15)),
+        (-10_064, 255, Bound::Exact, place(7, 3)),
+        (i32::MIN + 1, 17, Bound::Lower, place(3, 12)),
+    ] {
+        table.store(KEY, depth, score, bound, best);
+        let entry = table.probe(KEY).expect("the entry was just stored");
+        assert_eq!((entry.key, entry.score, entry.depth as u32, entry.bound, entry.best_move()), (KEY, score, depth, bound, best));
+        table.new_search();
+    }
+}
+
+#[test]
+fn depth_is_capped_and_passes_are_not_best_moves() {
+    let table = TranspositionTable::with_entries(16);
+    table.store(KEY, 1000, 5, Bound::Exact, Some(Move::Pass));
+    let entry = table.probe(KEY).unwrap();
+    assert_eq!(entry.depth, u8::MAX);
+    assert_eq!(entry.best_move(), None);
+}
+
+#[test]
+fn probe_misses_other_keys_and_empty_slots() {
+    let table = TranspositionTable::with_entries(16);
+    assert_eq!(table.probe(KEY), None);
+    // Even key 0 does not match an empty slot
+    assert_eq!(table.probe(0), None);
+    table.store(KEY, 3, 1, Bound::Exact, None);
+    assert_eq!(table.probe(OTHER), None);
+    table.clear();
+    assert_eq!(table.probe(KEY), None);
+}
+
+#[test]
+fn deeper_results_are_kept_within_a_search() {
+    let table = TranspositionTable::with_entries(16);
+    table.store(KEY, 3, 7, Bound::Exact, place(2, 3));
+    // A shallower result for another position does not evict it
+    table.store(OTHER, 1, 9, Bound::Exact, None);
+    assert_eq!(table.probe(OTHER), None);
+    // Nor does one for the same position, though its best move is taken
+    table.store(KEY, 1, -4, Bound::Lower, place(4, 5));
+    let entry = table.probe(KEY).unwrap();
+    assert_eq!((entry.depth, entry.score, entry.bound, entry.best_move()), (3, 7, Bound::Exact, place(4, 5)));
+    // As deep or deeper replaces, keeping the best move when none is given
+    table.store(KEY, 3, 2, Bound::Upper, None);
+    let entry = table.probe(KEY).unwrap();
+    assert_eq!((entry.depth, entry.score, entry.bound, entry.best_move()), (3, 2, Bound::Upper, place(4, 5)));
+}
+
+#[test]
+fn results_from_earlier_searches_give_way() {
+    let table = TranspositionTable::with_entries(16);
+    table.store(KEY, 9, 7, Bound::Exact, place(2, 3));
+    table.new_search();
+    table.store(OTHER, 1, 9, Bound::Lower, None);
+    assert_eq!(table.probe(KEY), None);
+    assert_eq!(table.probe(OTHER).map(|entry| entry.score), Some(9));
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use reversi::notation::parse_square;
+use reversi::wthor::{read_games, read_header, read_names, WthorError, PLAYER_LEN};
+use reversi::Move;
+
+use common::SHORTEST_GAME;
+
+// A header for `games` games on the standard board, written on 2003-12-15
+// for games played in 2003.
+fn header(games: u32) -> Vec<u8> {
+    let mut data = vec![20, 3, 12, 15];
+    data.extend(games.to_le_bytes());
+    data.extend([0, 0, 0xd3, 0x07, 8, 0, 22, 0]);
+    data
+}
+
+// A game record with the given moves in `10 * row + column` form.
+fn game(black: u16, white: u16, black_score: u8, moves: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend(7u16.to_le_bytes());
+    data.extend(black.to_le_bytes());
+    data.extend(white.to_le_bytes());
+    data.extend([black_score, black_score]);
+    data.extend(moves);
+    data.resize(68, 0);
+    data
+}
+
+// The moves of the shortest game, after which Black has every disc and so
+// every empty square.
+fn shortest() -> Vec<u8> {
+    SHORTEST_GAME
+        .iter()
+        .map(|square| {
+            let (row, col) = parse_square(square).expect("the game's squares are valid");
+            (10 * (row + 1) + col + 1) as u8
+        })
+        .collect()
+}
+
+#[test]
+fn reads_header_and_games() {
+    let mut data = header(2);
+    data.extend(game(1, 2, 64, &shortest()));
+    // An unfinished game scores just the discs on the board
+    data.extend(game(2, 1, 3, &[56, 64]));
+
+    let header = read_header(&data).expect("the header is complete");
+    assert_eq!(header.created, (2003, 12, 15));
+    assert_eq!((header.games, header.year, header.board_size, header.depth), (2, 2003, 8, 22));
+
+    let games: Vec<_> = read_games(&data).expect("the file is complete").collect::<Result<_, _>>().expect("the games are valid");
+    assert_eq!(games.len(), 2);
+    assert_eq!((games[0].tournament, games[0].black, games[0].white, games[0].black_score), (7, 1, 2, 64));
+    assert!(games[0].game.is_over());
+    assert_eq!(games[0].game.history().len(), SHORTEST_GAME.len());
+    assert_eq!(games[1].game.history()[1].mv, Move::Place { row: 5, col: 3 });
+}
+
+#[test]
+fn bad_games_do_not_stop_the_rest() {
+    let mut data = header(4);
+    data.extend(game(1, 2, 60, &shortest()));
+    data.extend(game(1, 2, 4, &[56, 11]));
+    data.extend(game(1, 2, 4, &[56, 90]));
+    data.extend(game(1, 2, 64, &shortest()));
+    let results: Vec<_> = read_games(&data).expect("the file is complete").collect();
+    assert_eq!(results[0].as_ref().unwrap_err(), &WthorError::ScoreMismatch { game: 1, recorded: 60, replayed: 64 });
+    assert_eq!(results[1].as_ref().unwrap_err(), &WthorError::IllegalMove { game: 2, index: 2, square: "a1".to_string() });
+    assert_eq!(results[2].as_ref().unwrap_err(), &WthorError::BadSquare { game: 3, index: 2, code: 90 });
+    assert!(results[3].is_ok());
+    assert_eq!(
+        results[0].as_ref().unwrap_err().to_string(),
+        "game 1: Black's score is recorded as 60 but the moves give 64"
+    );
+}
+
+#[test]
+fn short_or_foreign_files_are_errors() {
+    assert_eq!(read_header(&[0; 10]).unwrap_err(), WthorError::Truncated { expected: 16, found: 10 });
+    let mut data = header(2);
+    data.extend(game(1, 2, 64, &shortest()));
+    assert_eq!(read_games(&data).unwrap_err(), WthorError::Truncated { expected: 16 + 2 * 68, found: 16 + 68 });
+    let mut ten = header(0);
+    ten[12] = 10;
+    assert_eq!(read_games(&ten).unwrap_err(), WthorError::UnsupportedBoard(10));
+}
+
+#[test]
+fn reads_latin1_names() {
+    let mut data = header(0);
+    data[8] = 2;
+    let mut name = b"Tastet Marc".to_vec();
+    name.resize(PLAYER_LEN, 0);
+    data.extend(&name);
+    let mut name = vec![b'L', 0xe9, b'v', b'y', b' ', b' '];
+    name.resize(PLAYER_LEN, 0);
+    data.extend(&name);
+    assert_eq!(read_names(&data, PLAYER_LEN), Ok(vec!["Tastet Marc".to_string(), "Lévy".to_string()]));
+}
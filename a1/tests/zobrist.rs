@@ -0,0 +1,78 @@
+mod common;
+
+use reversi::zobrist::{bitboard_key, square_key, WHITE_TO_MOVE};
+use reversi::{Bitboard, Board, Cell};
+
+use common::next;
+
+// The hash of `board` computed square by square.
+fn scratch_hash(board: &Board) -> u64 {
+    let size = board.size();
+    (0..size).flat_map(|row| (0..size).map(move |col| (row, col))).fold(0, |hash, (row, col)| hash ^ square_key(row, col, board.get(row, col)))
+}
+
+#[test]
+fn incremental_hash_matches_scratch_hash() {
+    let mut state = 0x2545_f491_4f6c_dd1d;
+    for size in [4, 8, 10, 16] {
+        for _ in 0..20 {
+            let mut board = Board::with_size(size).unwrap();
+            let mut color = Cell::Black;
+            let mut played = Vec::new();
+            loop {
+                assert_eq!(board.hash(), scratch_hash(&board));
+                let moves = board.legal_moves(color);
+                if moves.is_empty() {
+                    if !board.has_valid_moves(color.opposite()) {
+                        break;
+                    }
+                    color = color.opposite();
+                    continue;
+                }
+                let pick = &moves[(next(&mut state) % moves.len() as u64) as usize];
+                let before = board.hash();
+                let flipped = board.apply_move(pick.row, pick.col, color);
+                // Taking a move back restores the hash exactly
+                let mut undone = board;
+                undone.undo_move(pick.row, pick.col, &flipped);
+                assert_eq!(undone.hash(), before);
+                played.push((pick.row, pick.col, flipped));
+                color = color.opposite();
+            }
+            while let Some((row, col, flipped)) = played.pop() {
+                board.undo_move(row, col, &flipped);
+                assert_eq!(board.hash(), scratch_hash(&board));
+            }
+            assert_eq!(board, Board::with_size(size).unwrap());
+        }
+    }
+}
+
+#[test]
+fn set_keeps_the_hash_in_step() {
+    let mut board = Board::empty();
+    assert_eq!(board.hash(), 0);
+    board.set(2, 5, Cell::Black);
+    board.set(2, 5, Cell::White);
+    board.set(7, 0, Cell::Black);
+    assert_eq!(board.hash(), scratch_hash(&board));
+    board.set(2, 5, Cell::Empty);
+    board.set(7, 0, Cell::Empty);
+    assert_eq!(board.hash(), 0);
+}
+
+#[test]
+fn side_to_move_changes_the_key() {
+    let board = Board::new();
+    assert_eq!(board.key(Cell::Black), board.hash());
+    assert_eq!(board.key(Cell::White), board.hash() ^ WHITE_TO_MOVE);
+}
+
+#[test]
+fn bitboard_key_matches_board_hash() {
+    let board = Board::new();
+    let bitboard = Bitboard::try_from(&board).unwrap();
+    // From Black's side the mover's discs are black, so the keys agree
+    assert_eq!(bitboard_key(bitboard.black, bitboard.white), board.hash());
+    assert_ne!(bitboard_key(bitboard.white, bitboard.black), board.hash());
+}